@@ -0,0 +1,118 @@
+use crate::ieee80211::field::{FrameControl, MACAddr};
+use libc::sock_filter;
+
+/// 802.11 frames to accept, fields left as `None` match any frame.
+#[derive(Debug, Clone, Default)]
+pub struct FrameMatch {
+    /// Frame type in frame control position, e.g. `FrameControl::TYPE_MGMT`.
+    pub frame_type: Option<u16>,
+    /// Frame subtype in frame control position, e.g. `FrameControl::SUB_TYPE_BEACON`.
+    pub sub_type: Option<u16>,
+    pub addr1: Option<MACAddr>,
+    pub addr2: Option<MACAddr>,
+    pub addr3: Option<MACAddr>,
+}
+
+impl FrameMatch {
+    pub fn management(sub_type: u16) -> Self {
+        Self {
+            frame_type: Some(FrameControl::TYPE_MGMT),
+            sub_type: Some(sub_type),
+            ..Self::default()
+        }
+    }
+}
+
+/// Compiles a classic BPF program for radiotap packets that accepts a frame
+/// if any of `matches` does, and drops all other frames in the kernel.
+pub fn compile(matches: &[FrameMatch]) -> Vec<sock_filter> {
+    // X = radiotap length, a little-endian u16 at offset 2.
+    let mut program = vec![
+        stmt(libc::BPF_LD | libc::BPF_B | libc::BPF_ABS, 3),
+        stmt(libc::BPF_ALU | libc::BPF_LSH | libc::BPF_K, 8),
+        stmt(libc::BPF_MISC | BPF_TAX, 0),
+        stmt(libc::BPF_LD | libc::BPF_B | libc::BPF_ABS, 2),
+        stmt(libc::BPF_ALU | libc::BPF_OR | libc::BPF_X, 0),
+        stmt(libc::BPF_MISC | BPF_TAX, 0),
+    ];
+    for frame_match in matches {
+        program.extend(compile_match(frame_match));
+    }
+    program.push(stmt(libc::BPF_RET | libc::BPF_K, DROP));
+    program
+}
+
+/// Compiles the checks of one match followed by an accept. A failed check
+/// jumps past the accept, to the next match.
+fn compile_match(frame_match: &FrameMatch) -> Vec<sock_filter> {
+    let mut checks = vec![];
+    let mask = frame_match.frame_type.map_or(0, |_| FrameControl::TYPE)
+        | frame_match.sub_type.map_or(0, |_| FrameControl::SUB_TYPE);
+    if mask != 0 {
+        let value = frame_match.frame_type.unwrap_or(0) | frame_match.sub_type.unwrap_or(0);
+        // Type and subtype are both in the first frame control byte.
+        checks.push(stmt(libc::BPF_LD | libc::BPF_B | libc::BPF_IND, 0));
+        checks.push(stmt(
+            libc::BPF_ALU | libc::BPF_AND | libc::BPF_K,
+            mask as u32,
+        ));
+        checks.push(jeq(value as u32));
+    }
+    let addrs = [
+        (ADDR1_OFFSET, &frame_match.addr1),
+        (ADDR2_OFFSET, &frame_match.addr2),
+        (ADDR3_OFFSET, &frame_match.addr3),
+    ];
+    for (offset, addr) in addrs {
+        if let Some(addr) = addr {
+            let addr = addr.as_bytes();
+            checks.push(stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_IND, offset));
+            checks.push(jeq(u32::from_be_bytes([
+                addr[0], addr[1], addr[2], addr[3],
+            ])));
+            checks.push(stmt(libc::BPF_LD | libc::BPF_H | libc::BPF_IND, offset + 4));
+            checks.push(jeq(u16::from_be_bytes([addr[4], addr[5]]) as u32));
+        }
+    }
+    checks.push(stmt(libc::BPF_RET | libc::BPF_K, ACCEPT));
+    let len = checks.len();
+    for (i, insn) in checks.iter_mut().enumerate() {
+        if insn.code == JEQ {
+            insn.jf = (len - i - 1) as u8;
+        }
+    }
+    checks
+}
+
+/// Return value accepting the whole packet.
+const ACCEPT: u32 = u32::MAX;
+const DROP: u32 = 0;
+
+/// `BPF_MISC` operation copying A to X, missing from libc.
+const BPF_TAX: u32 = 0x00;
+
+const JEQ: u16 = (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16;
+
+/// Offsets of the addresses from the start of the 802.11 header.
+const ADDR1_OFFSET: u32 = 4;
+const ADDR2_OFFSET: u32 = 10;
+const ADDR3_OFFSET: u32 = 16;
+
+fn stmt(code: u32, k: u32) -> sock_filter {
+    sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+/// Falls through on equality, the jump on mismatch is set by `compile_match`.
+fn jeq(k: u32) -> sock_filter {
+    sock_filter {
+        code: JEQ,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
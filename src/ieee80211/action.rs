@@ -0,0 +1,478 @@
+use super::element::InfoElementIter;
+use super::field::{MACAddr, ReasonCode, StatusCode};
+use super::regulatory::ChannelSwitch;
+use crate::misc::{slice_at, MemCast, ParseError};
+use core::fmt::{self, Display, Formatter};
+use core::mem;
+
+/// Action frame category, the first byte of the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionCategory {
+    SpectrumManagement,
+    QoS,
+    BlockAck,
+    Public,
+    RadioMeasurement,
+    FastBSSTransition,
+    HT,
+    SAQuery,
+    ProtectedDualOfPublic,
+    WNM,
+    UnprotectedWNM,
+    TDLS,
+    Mesh,
+    SelfProtected,
+    FST,
+    VHT,
+    HE,
+    ProtectedHE,
+    VendorSpecificProtected,
+    VendorSpecific,
+    Other(u8),
+}
+
+impl From<u8> for ActionCategory {
+    fn from(v: u8) -> Self {
+        match v {
+            0 => ActionCategory::SpectrumManagement,
+            1 => ActionCategory::QoS,
+            3 => ActionCategory::BlockAck,
+            4 => ActionCategory::Public,
+            5 => ActionCategory::RadioMeasurement,
+            6 => ActionCategory::FastBSSTransition,
+            7 => ActionCategory::HT,
+            8 => ActionCategory::SAQuery,
+            9 => ActionCategory::ProtectedDualOfPublic,
+            10 => ActionCategory::WNM,
+            11 => ActionCategory::UnprotectedWNM,
+            12 => ActionCategory::TDLS,
+            13 => ActionCategory::Mesh,
+            15 => ActionCategory::SelfProtected,
+            18 => ActionCategory::FST,
+            21 => ActionCategory::VHT,
+            30 => ActionCategory::HE,
+            31 => ActionCategory::ProtectedHE,
+            126 => ActionCategory::VendorSpecificProtected,
+            127 => ActionCategory::VendorSpecific,
+            v => ActionCategory::Other(v),
+        }
+    }
+}
+
+impl ActionCategory {
+    fn is_vendor_specific(&self) -> bool {
+        matches!(
+            self,
+            ActionCategory::VendorSpecific | ActionCategory::VendorSpecificProtected
+        )
+    }
+}
+
+impl Display for ActionCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ActionCategory::SpectrumManagement => "spectrum management",
+            ActionCategory::QoS => "QoS",
+            ActionCategory::BlockAck => "Block Ack",
+            ActionCategory::Public => "public",
+            ActionCategory::RadioMeasurement => "radio measurement",
+            ActionCategory::FastBSSTransition => "FT",
+            ActionCategory::HT => "HT",
+            ActionCategory::SAQuery => "SA Query",
+            ActionCategory::ProtectedDualOfPublic => "protected dual of public",
+            ActionCategory::WNM => "WNM",
+            ActionCategory::UnprotectedWNM => "unprotected WNM",
+            ActionCategory::TDLS => "TDLS",
+            ActionCategory::Mesh => "mesh",
+            ActionCategory::SelfProtected => "self-protected",
+            ActionCategory::FST => "FST",
+            ActionCategory::VHT => "VHT",
+            ActionCategory::HE => "HE",
+            ActionCategory::ProtectedHE => "protected HE",
+            ActionCategory::VendorSpecificProtected => "protected vendor-specific",
+            ActionCategory::VendorSpecific => "vendor-specific",
+            ActionCategory::Other(v) => return write!(f, "category {}", v),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Body of an action or action no-ack frame.
+#[derive(Debug)]
+pub struct ActionFrame<'a> {
+    pub category: ActionCategory,
+    /// Action code, absent for vendor-specific categories.
+    pub code: Option<u8>,
+    pub details: ActionDetails<'a>,
+}
+
+/// Decoded action fields, for the actions with a typed view.
+#[derive(Debug)]
+pub enum ActionDetails<'a> {
+    /// Channel switch announcement, or its extended public form.
+    ChannelSwitch(ChannelSwitch),
+    AddBARequest(AddBARequest),
+    AddBAResponse(AddBAResponse),
+    DelBA(DelBA),
+    SAQueryRequest {
+        transaction_id: u16,
+    },
+    SAQueryResponse {
+        transaction_id: u16,
+    },
+    NeighborReportRequest {
+        dialog_token: u8,
+        elements: InfoElementIter<'a>,
+    },
+    NeighborReportResponse {
+        dialog_token: u8,
+        /// Neighbor report elements.
+        reports: InfoElementIter<'a>,
+    },
+    BTMQuery {
+        dialog_token: u8,
+        reason: u8,
+        candidates: InfoElementIter<'a>,
+    },
+    BTMRequest(BTMRequest<'a>),
+    BTMResponse(BTMResponse<'a>),
+    VendorSpecific {
+        oui: [u8; 3],
+        data: &'a [u8],
+    },
+    /// Action without a typed view, body after the action code.
+    Other(&'a [u8]),
+}
+
+impl<'a> ActionFrame<'a> {
+    /// Parses the frame body, starting at the category.
+    pub fn parse(body: &'a [u8]) -> Result<Self, ParseError> {
+        let category = ActionCategory::from(slice_at(body, 0, 1)?[0]);
+        if category.is_vendor_specific() {
+            let oui = slice_at(body, 1, 3)?.try_into().unwrap();
+            return Ok(Self {
+                category,
+                code: None,
+                details: ActionDetails::VendorSpecific {
+                    oui,
+                    data: &body[4..],
+                },
+            });
+        }
+        let code = slice_at(body, 1, 1)?[0];
+        let fields = &body[2..];
+        use ActionCategory as C;
+        use ActionDetails as D;
+        let details = match (category, code) {
+            (C::SpectrumManagement, 4) => D::ChannelSwitch(ChannelSwitch::from_element(fields)?),
+            (C::Public | C::ProtectedDualOfPublic, 4) => {
+                D::ChannelSwitch(ChannelSwitch::new_extended(fields)?)
+            }
+            (C::BlockAck, 0) => D::AddBARequest(AddBARequest {
+                dialog_token: slice_at(fields, 0, 1)?[0],
+                parameters: BlockAckParameters(u16_at(fields, 1)?),
+                timeout: u16_at(fields, 3)?,
+                starting_sequence: u16_at(fields, 5)? >> 4,
+            }),
+            (C::BlockAck, 1) => D::AddBAResponse(AddBAResponse {
+                dialog_token: slice_at(fields, 0, 1)?[0],
+                status: StatusCode(u16_at(fields, 1)?),
+                parameters: BlockAckParameters(u16_at(fields, 3)?),
+                timeout: u16_at(fields, 5)?,
+            }),
+            (C::BlockAck, 2) => D::DelBA(DelBA {
+                parameters: u16_at(fields, 0)?,
+                reason: ReasonCode(u16_at(fields, 2)?),
+            }),
+            (C::SAQuery, 0) => D::SAQueryRequest {
+                transaction_id: u16_at(fields, 0)?,
+            },
+            (C::SAQuery, 1) => D::SAQueryResponse {
+                transaction_id: u16_at(fields, 0)?,
+            },
+            (C::RadioMeasurement, 4) => D::NeighborReportRequest {
+                dialog_token: slice_at(fields, 0, 1)?[0],
+                elements: InfoElementIter::new(&fields[1..]),
+            },
+            (C::RadioMeasurement, 5) => D::NeighborReportResponse {
+                dialog_token: slice_at(fields, 0, 1)?[0],
+                reports: InfoElementIter::new(&fields[1..]),
+            },
+            (C::WNM, 6) => D::BTMQuery {
+                dialog_token: slice_at(fields, 0, 1)?[0],
+                reason: slice_at(fields, 1, 1)?[0],
+                candidates: InfoElementIter::new(&fields[2..]),
+            },
+            (C::WNM, 7) => D::BTMRequest(BTMRequest::parse(fields)?),
+            (C::WNM, 8) => D::BTMResponse(BTMResponse::parse(fields)?),
+            _ => D::Other(fields),
+        };
+        Ok(Self {
+            category,
+            code: Some(code),
+            details,
+        })
+    }
+
+    /// Name of the action within its category, when known.
+    pub fn name(&self) -> Option<&'static str> {
+        use ActionCategory as C;
+        Some(match (self.category, self.code?) {
+            (C::SpectrumManagement, 0) => "measurement request",
+            (C::SpectrumManagement, 1) => "measurement report",
+            (C::SpectrumManagement, 2) => "TPC request",
+            (C::SpectrumManagement, 3) => "TPC report",
+            (C::SpectrumManagement, 4) => "channel switch announcement",
+            (C::QoS, 0) => "ADDTS request",
+            (C::QoS, 1) => "ADDTS response",
+            (C::QoS, 2) => "DELTS",
+            (C::QoS, 4) => "QoS map configure",
+            (C::BlockAck, 0) => "ADDBA request",
+            (C::BlockAck, 1) => "ADDBA response",
+            (C::BlockAck, 2) => "DELBA",
+            (C::Public | C::ProtectedDualOfPublic, 0) => "20/40 BSS coexistence",
+            (C::Public | C::ProtectedDualOfPublic, 4) => "extended channel switch announcement",
+            (C::Public | C::ProtectedDualOfPublic, 9) => "vendor-specific",
+            (C::Public | C::ProtectedDualOfPublic, 10) => "GAS initial request",
+            (C::Public | C::ProtectedDualOfPublic, 11) => "GAS initial response",
+            (C::Public | C::ProtectedDualOfPublic, 12) => "GAS comeback request",
+            (C::Public | C::ProtectedDualOfPublic, 13) => "GAS comeback response",
+            (C::Public, 32) => "FTM request",
+            (C::Public, 33) => "FTM",
+            (C::RadioMeasurement, 0) => "radio measurement request",
+            (C::RadioMeasurement, 1) => "radio measurement report",
+            (C::RadioMeasurement, 2) => "link measurement request",
+            (C::RadioMeasurement, 3) => "link measurement report",
+            (C::RadioMeasurement, 4) => "neighbor report request",
+            (C::RadioMeasurement, 5) => "neighbor report response",
+            (C::FastBSSTransition, 1) => "FT request",
+            (C::FastBSSTransition, 2) => "FT response",
+            (C::FastBSSTransition, 3) => "FT confirm",
+            (C::FastBSSTransition, 4) => "FT ack",
+            (C::HT, 0) => "notify channel width",
+            (C::HT, 1) => "SM power save",
+            (C::SAQuery, 0) => "SA Query request",
+            (C::SAQuery, 1) => "SA Query response",
+            (C::WNM, 6) => "BSS transition management query",
+            (C::WNM, 7) => "BSS transition management request",
+            (C::WNM, 8) => "BSS transition management response",
+            (C::SelfProtected, 1) => "mesh peering open",
+            (C::SelfProtected, 2) => "mesh peering confirm",
+            (C::SelfProtected, 3) => "mesh peering close",
+            (C::FST, 0) => "FST setup request",
+            (C::FST, 1) => "FST setup response",
+            (C::FST, 2) => "FST teardown",
+            (C::FST, 3) => "FST ack request",
+            (C::FST, 4) => "FST ack response",
+            (C::VHT, 0) => "VHT compressed beamforming",
+            (C::VHT, 1) => "group ID management",
+            (C::VHT, 2) => "operating mode notification",
+            (C::HE, 0) => "HE compressed beamforming and CQI",
+            (C::HE, 1) => "quiet time period",
+            (C::HE, 2) => "OPS",
+            _ => return None,
+        })
+    }
+}
+
+fn u16_at(buf: &[u8], offset: usize) -> Result<u16, ParseError> {
+    let bytes = slice_at(buf, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Block Ack parameter set of ADDBA frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockAckParameters(pub u16);
+
+impl BlockAckParameters {
+    pub fn is_amsdu_supported(&self) -> bool {
+        self.0 & 0x0001 != 0
+    }
+
+    /// Immediate rather than delayed Block Ack.
+    pub fn is_immediate(&self) -> bool {
+        self.0 & 0x0002 != 0
+    }
+
+    pub fn tid(&self) -> u8 {
+        ((self.0 >> 2) & 0x0f) as u8
+    }
+
+    pub fn buffer_size(&self) -> u16 {
+        self.0 >> 6
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddBARequest {
+    pub dialog_token: u8,
+    pub parameters: BlockAckParameters,
+    /// Block Ack timeout in TUs, 0 if disabled.
+    pub timeout: u16,
+    pub starting_sequence: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddBAResponse {
+    pub dialog_token: u8,
+    pub status: StatusCode,
+    pub parameters: BlockAckParameters,
+    pub timeout: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelBA {
+    parameters: u16,
+    pub reason: ReasonCode,
+}
+
+impl DelBA {
+    /// Sent by the originator of the agreement rather than the recipient.
+    pub fn is_initiator(&self) -> bool {
+        self.parameters & 0x0800 != 0
+    }
+
+    pub fn tid(&self) -> u8 {
+        (self.parameters >> 12) as u8
+    }
+}
+
+/// BSS termination duration subelement of a BTM request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BSSTermination {
+    /// TSF at which the BSS terminates.
+    pub tsf: u64,
+    pub duration_minutes: u16,
+}
+
+#[derive(Debug)]
+pub struct BTMRequest<'a> {
+    pub dialog_token: u8,
+    mode: u8,
+    /// Beacon intervals until the AP disassociates the station, 0 if unspecified.
+    pub disassociation_timer: u16,
+    /// Beacon intervals the candidate list is valid for.
+    pub validity_interval: u8,
+    pub bss_termination: Option<BSSTermination>,
+    pub session_info_url: Option<&'a [u8]>,
+    candidates: &'a [u8],
+}
+
+impl<'a> BTMRequest<'a> {
+    fn parse(fields: &'a [u8]) -> Result<Self, ParseError> {
+        let fixed = slice_at(fields, 0, 5)?;
+        let mode = fixed[1];
+        let mut offset = 5;
+        let mut bss_termination = None;
+        if mode & 0x08 != 0 {
+            let sub = slice_at(fields, offset, 12)?;
+            bss_termination = Some(BSSTermination {
+                tsf: u64::from_le_bytes(sub[2..10].try_into().unwrap()),
+                duration_minutes: u16::from_le_bytes([sub[10], sub[11]]),
+            });
+            offset += 12;
+        }
+        let mut session_info_url = None;
+        if mode & 0x10 != 0 {
+            let len = slice_at(fields, offset, 1)?[0] as usize;
+            session_info_url = Some(slice_at(fields, offset + 1, len)?);
+            offset += 1 + len;
+        }
+        Ok(Self {
+            dialog_token: fixed[0],
+            mode,
+            disassociation_timer: u16::from_le_bytes([fixed[2], fixed[3]]),
+            validity_interval: fixed[4],
+            bss_termination,
+            session_info_url,
+            candidates: &fields[offset..],
+        })
+    }
+
+    pub fn has_preferred_candidates(&self) -> bool {
+        self.mode & 0x01 != 0
+    }
+
+    /// Unlisted BSSs are not preferred over the candidates.
+    pub fn is_abridged(&self) -> bool {
+        self.mode & 0x02 != 0
+    }
+
+    pub fn is_disassociation_imminent(&self) -> bool {
+        self.mode & 0x04 != 0
+    }
+
+    pub fn is_ess_disassociation_imminent(&self) -> bool {
+        self.mode & 0x10 != 0
+    }
+
+    /// Neighbor report elements of the candidate list.
+    pub fn candidates(&self) -> InfoElementIter<'a> {
+        InfoElementIter::new(self.candidates)
+    }
+}
+
+/// BSS transition management status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BTMStatus(pub u8);
+
+impl BTMStatus {
+    pub fn is_accept(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Display for BTMStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self.0 {
+            0 => "accept",
+            1 => "reject, unspecified",
+            2 => "reject, insufficient beacons",
+            3 => "reject, insufficient capacity",
+            4 => "reject, BSS termination undesired",
+            5 => "reject, BSS termination delay requested",
+            6 => "reject, candidate list provided",
+            7 => "reject, no suitable candidates",
+            8 => "reject, leaving ESS",
+            v => return write!(f, "status {}", v),
+        };
+        write!(f, "{} ({})", name, self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct BTMResponse<'a> {
+    pub dialog_token: u8,
+    pub status: BTMStatus,
+    /// Minutes the station asks the AP to delay BSS termination.
+    pub termination_delay: u8,
+    /// BSS the station transitions to, present on accept.
+    pub target_bssid: Option<&'a MACAddr>,
+    candidates: &'a [u8],
+}
+
+impl<'a> BTMResponse<'a> {
+    fn parse(fields: &'a [u8]) -> Result<Self, ParseError> {
+        let fixed = slice_at(fields, 0, 3)?;
+        let status = BTMStatus(fixed[1]);
+        let mut offset = 3;
+        let mut target_bssid = None;
+        if status.is_accept() {
+            let len = mem::size_of::<MACAddr>();
+            target_bssid = Some(slice_at(fields, offset, len)?.try_cast_ref()?);
+            offset += len;
+        }
+        Ok(Self {
+            dialog_token: fixed[0],
+            status,
+            termination_delay: fixed[2],
+            target_bssid,
+            candidates: &fields[offset..],
+        })
+    }
+
+    /// Neighbor report elements the station prefers.
+    pub fn candidates(&self) -> InfoElementIter<'a> {
+        InfoElementIter::new(self.candidates)
+    }
+}
@@ -0,0 +1,180 @@
+/// Traffic Indication Map element.
+pub struct TIM<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> TIM<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 4).then_some(Self { data })
+    }
+
+    /// Beacons until the next DTIM, 0 if this beacon is one.
+    pub fn dtim_count(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Beacon intervals between DTIMs.
+    pub fn dtim_period(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// Group addressed traffic is buffered, only meaningful in DTIMs.
+    pub fn has_multicast(&self) -> bool {
+        self.data[2] & 0x01 != 0
+    }
+
+    /// Byte offset of the partial virtual bitmap in the full one.
+    pub fn bitmap_offset(&self) -> usize {
+        (self.data[2] & 0xfe) as usize
+    }
+
+    pub fn partial_virtual_bitmap(&self) -> &'a [u8] {
+        &self.data[3..]
+    }
+
+    /// Association IDs of stations with buffered unicast traffic.
+    pub fn aids(&self) -> impl Iterator<Item = u16> + 'a {
+        let offset = self.bitmap_offset();
+        self.partial_virtual_bitmap()
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, &byte)| {
+                (0..8)
+                    .filter(move |bit| byte & (1 << bit) != 0)
+                    .map(move |bit| ((offset + i) * 8 + bit) as u16)
+            })
+            .filter(|&aid| aid != 0)
+    }
+}
+
+/// BSS Load element, also known as QBSS Load.
+pub struct BSSLoad<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> BSSLoad<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 5).then_some(Self { data })
+    }
+
+    /// Associated stations.
+    pub fn station_count(&self) -> u16 {
+        u16::from_le_bytes([self.data[0], self.data[1]])
+    }
+
+    /// Time the AP sensed the medium busy, out of 255.
+    pub fn channel_utilization(&self) -> u8 {
+        self.data[2]
+    }
+
+    pub fn channel_utilization_percent(&self) -> u8 {
+        ((self.channel_utilization() as u16 * 100 + 127) / 255) as u8
+    }
+
+    /// Remaining medium time for admission control, in units of 32 us per second.
+    pub fn admission_capacity(&self) -> u16 {
+        u16::from_le_bytes([self.data[3], self.data[4]])
+    }
+}
+
+/// AP Channel Report element, channels of an operating class where other APs
+/// may be found.
+pub struct APChannelReport<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> APChannelReport<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (!data.is_empty()).then_some(Self { data })
+    }
+
+    pub fn operating_class(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn channels(&self) -> &'a [u8] {
+        &self.data[1..]
+    }
+}
+
+/// Extended Capabilities element, a bit field that may end at any byte.
+pub struct ExtendedCapabilities<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> ExtendedCapabilities<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Capability `bit`, unset when the element ends before it.
+    pub fn has(&self, bit: usize) -> bool {
+        self.data
+            .get(bit / 8)
+            .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+    }
+
+    /// 20/40 BSS Coexistence Management support.
+    pub fn has_coexistence_management(&self) -> bool {
+        self.has(0)
+    }
+
+    pub fn has_extended_channel_switching(&self) -> bool {
+        self.has(2)
+    }
+
+    pub fn has_proxy_arp(&self) -> bool {
+        self.has(12)
+    }
+
+    /// BSS transition management, the 802.11v steering of stations.
+    pub fn has_bss_transition(&self) -> bool {
+        self.has(19)
+    }
+
+    pub fn has_multiple_bssid(&self) -> bool {
+        self.has(22)
+    }
+
+    /// Interworking with external networks, as used by Hotspot 2.0.
+    pub fn has_interworking(&self) -> bool {
+        self.has(31)
+    }
+
+    pub fn has_qos_map(&self) -> bool {
+        self.has(32)
+    }
+
+    pub fn has_utf8_ssid(&self) -> bool {
+        self.has(48)
+    }
+
+    pub fn has_operating_mode_notification(&self) -> bool {
+        self.has(62)
+    }
+
+    /// Fine timing measurement responder, for round trip time ranging.
+    pub fn is_ftm_responder(&self) -> bool {
+        self.has(70)
+    }
+
+    pub fn is_ftm_initiator(&self) -> bool {
+        self.has(71)
+    }
+
+    pub fn is_twt_requester(&self) -> bool {
+        self.has(77)
+    }
+
+    pub fn is_twt_responder(&self) -> bool {
+        self.has(78)
+    }
+
+    pub fn has_sae_password_ids(&self) -> bool {
+        self.has(81)
+    }
+
+    pub fn has_beacon_protection(&self) -> bool {
+        self.has(84)
+    }
+}
@@ -0,0 +1,421 @@
+use super::element::InfoElement;
+use super::field::{FrameControl, MACAddr};
+
+/// Owned management frame, serialized without FCS by [`ManagementFrame::to_bytes`].
+#[derive(Debug, Clone)]
+pub struct ManagementFrame {
+    pub duration: u16,
+    pub addr1: MACAddr,
+    pub addr2: MACAddr,
+    pub addr3: MACAddr,
+    /// Sequence number, 12 bits.
+    pub sequence: u16,
+    /// Fragment number, 4 bits.
+    pub fragment: u8,
+    pub body: ManagementBody,
+}
+
+/// Fixed fields and elements of a management frame body. Integer fields are
+/// written little-endian as they appear on air.
+#[derive(Debug, Clone)]
+pub enum ManagementBody {
+    Beacon {
+        timestamp: u64,
+        /// Beacon interval in TUs.
+        interval: u16,
+        capability: u16,
+        elements: Elements,
+    },
+    ProbeRequest {
+        elements: Elements,
+    },
+    ProbeResponse {
+        timestamp: u64,
+        interval: u16,
+        capability: u16,
+        elements: Elements,
+    },
+    Auth {
+        algorithm: u16,
+        /// Transaction sequence number.
+        sequence: u16,
+        status: u16,
+        elements: Elements,
+    },
+    AssocRequest {
+        capability: u16,
+        listen_interval: u16,
+        elements: Elements,
+    },
+    AssocResponse {
+        capability: u16,
+        status: u16,
+        /// Association ID, without the two most significant bits set.
+        aid: u16,
+        elements: Elements,
+    },
+    Deauth {
+        reason: u16,
+    },
+    Disassoc {
+        reason: u16,
+    },
+    Action {
+        category: u8,
+        /// Action field and body following the category.
+        data: Vec<u8>,
+    },
+}
+
+impl ManagementBody {
+    /// Frame control subtype of the body.
+    pub fn sub_type(&self) -> u16 {
+        match self {
+            ManagementBody::Beacon { .. } => FrameControl::SUB_TYPE_BEACON,
+            ManagementBody::ProbeRequest { .. } => FrameControl::SUB_TYPE_PROBE_REQ,
+            ManagementBody::ProbeResponse { .. } => FrameControl::SUB_TYPE_PROBE_RESP,
+            ManagementBody::Auth { .. } => FrameControl::SUB_TYPE_AUTH,
+            ManagementBody::AssocRequest { .. } => FrameControl::SUB_TYPE_ASSOC_REQ,
+            ManagementBody::AssocResponse { .. } => FrameControl::SUB_TYPE_ASSOC_RESP,
+            ManagementBody::Deauth { .. } => FrameControl::SUB_TYPE_DEAUTH,
+            ManagementBody::Disassoc { .. } => FrameControl::SUB_TYPE_DISASSOC,
+            ManagementBody::Action { .. } => FrameControl::SUB_TYPE_ACTION,
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            ManagementBody::Beacon {
+                timestamp,
+                interval,
+                capability,
+                elements,
+            }
+            | ManagementBody::ProbeResponse {
+                timestamp,
+                interval,
+                capability,
+                elements,
+            } => {
+                buf.extend(timestamp.to_le_bytes());
+                buf.extend(interval.to_le_bytes());
+                buf.extend(capability.to_le_bytes());
+                buf.extend(elements.as_bytes());
+            }
+            ManagementBody::ProbeRequest { elements } => buf.extend(elements.as_bytes()),
+            ManagementBody::Auth {
+                algorithm,
+                sequence,
+                status,
+                elements,
+            } => {
+                buf.extend(algorithm.to_le_bytes());
+                buf.extend(sequence.to_le_bytes());
+                buf.extend(status.to_le_bytes());
+                buf.extend(elements.as_bytes());
+            }
+            ManagementBody::AssocRequest {
+                capability,
+                listen_interval,
+                elements,
+            } => {
+                buf.extend(capability.to_le_bytes());
+                buf.extend(listen_interval.to_le_bytes());
+                buf.extend(elements.as_bytes());
+            }
+            ManagementBody::AssocResponse {
+                capability,
+                status,
+                aid,
+                elements,
+            } => {
+                buf.extend(capability.to_le_bytes());
+                buf.extend(status.to_le_bytes());
+                // The two most significant bits are set on air.
+                buf.extend((aid | 0xc000).to_le_bytes());
+                buf.extend(elements.as_bytes());
+            }
+            ManagementBody::Deauth { reason } | ManagementBody::Disassoc { reason } => {
+                buf.extend(reason.to_le_bytes())
+            }
+            ManagementBody::Action { category, data } => {
+                buf.push(*category);
+                buf.extend(data);
+            }
+        }
+    }
+}
+
+impl ManagementFrame {
+    pub fn new(addr1: MACAddr, addr2: MACAddr, addr3: MACAddr, body: ManagementBody) -> Self {
+        Self {
+            duration: 0,
+            addr1,
+            addr2,
+            addr3,
+            sequence: 0,
+            fragment: 0,
+            body,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let control = FrameControl::TYPE_MGMT | self.body.sub_type();
+        let seq_control = self.sequence << 4 | (self.fragment & 0x0f) as u16;
+        let mut buf = Vec::with_capacity(64);
+        buf.extend(control.to_le_bytes());
+        buf.extend(self.duration.to_le_bytes());
+        buf.extend(self.addr1.as_bytes());
+        buf.extend(self.addr2.as_bytes());
+        buf.extend(self.addr3.as_bytes());
+        buf.extend(seq_control.to_le_bytes());
+        self.body.encode(&mut buf);
+        buf
+    }
+}
+
+/// Encoded information elements of a frame body, in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct Elements(Vec<u8>);
+
+impl Elements {
+    /// Appends an element, split into Fragment elements if the body is longer
+    /// than 255 bytes.
+    pub fn with(mut self, element: InfoElement<'_>) -> Self {
+        element.encode(&mut self.0);
+        self
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ieee80211::action::{ActionCategory, ActionDetails};
+    use crate::ieee80211::element::InfoElementIter;
+    use crate::ieee80211::field::{AuthAlgorithm, FrameType};
+    use crate::ieee80211::frame::{
+        Action, AssociationRequest, AssociationResponse, Authentication, Beacon, Deauthentication,
+        Disassociation, Frame, Management, ProbeRequest, ProbeResponse,
+    };
+
+    const AP: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+    const STA: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x02];
+
+    fn frame(body: ManagementBody) -> Vec<u8> {
+        let mut frame = ManagementFrame::new(STA.into(), AP.into(), AP.into(), body);
+        frame.sequence = 0x123;
+        frame.fragment = 1;
+        frame.to_bytes()
+    }
+
+    fn elements() -> Elements {
+        Elements::default()
+            .with(InfoElement::SSID(b"wifi"))
+            .with(InfoElement::DSSS(6))
+    }
+
+    /// Asserts the elements built by [`elements`].
+    fn assert_elements(iter: InfoElementIter) {
+        let elements: Vec<_> = iter.collect::<Result<_, _>>().unwrap();
+        assert_eq!(elements.len(), 2);
+        assert!(matches!(elements[0], InfoElement::SSID(b"wifi")));
+        assert!(matches!(elements[1], InfoElement::DSSS(6)));
+    }
+
+    #[test]
+    fn header() {
+        let frame = frame(ManagementBody::Deauth { reason: 3 });
+        assert_eq!(frame.control().unwrap().frame_type(), FrameType::Deauth);
+        assert_eq!(frame.addr1().unwrap(), &MACAddr::from(STA));
+        assert_eq!(frame.addr2().unwrap(), &MACAddr::from(AP));
+        assert_eq!(frame.bssid().unwrap(), &MACAddr::from(AP));
+        assert_eq!(frame.seq_control().unwrap(), &[0x31, 0x12]);
+    }
+
+    #[test]
+    fn beacon() {
+        let frame = frame(ManagementBody::Beacon {
+            timestamp: 0x0102030405060708,
+            interval: 100,
+            capability: 0x0011,
+            elements: elements(),
+        });
+        let frame = frame.as_slice();
+        assert_eq!(frame.control().unwrap().frame_type(), FrameType::Beacon);
+        assert_eq!(
+            u64::from_le_bytes(*Beacon::timestamp(frame).unwrap()),
+            0x0102030405060708
+        );
+        assert_eq!(Beacon::interval(frame).unwrap().tu(), 100);
+        let capability = Beacon::capability(frame).unwrap();
+        assert!(capability.has_ess() && capability.has_privacy() && !capability.has_ibss());
+        assert_elements(Beacon::info_elements(frame).unwrap());
+    }
+
+    #[test]
+    fn probe_request() {
+        let frame = frame(ManagementBody::ProbeRequest {
+            elements: elements(),
+        });
+        assert_eq!(
+            frame.control().unwrap().frame_type(),
+            FrameType::ProbeRequest
+        );
+        assert_elements(ProbeRequest::info_elements(frame.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn probe_response() {
+        let frame = frame(ManagementBody::ProbeResponse {
+            timestamp: 42,
+            interval: 200,
+            capability: 0x0002,
+            elements: elements(),
+        });
+        let frame = frame.as_slice();
+        assert_eq!(
+            frame.control().unwrap().frame_type(),
+            FrameType::ProbeResponse
+        );
+        assert_eq!(
+            u64::from_le_bytes(*ProbeResponse::timestamp(frame).unwrap()),
+            42
+        );
+        assert_eq!(ProbeResponse::interval(frame).unwrap().tu(), 200);
+        assert!(ProbeResponse::capability(frame).unwrap().has_ibss());
+        assert_elements(ProbeResponse::info_elements(frame).unwrap());
+    }
+
+    #[test]
+    fn authentication() {
+        let frame = frame(ManagementBody::Auth {
+            algorithm: 0,
+            sequence: 1,
+            status: 0,
+            elements: elements(),
+        });
+        let frame = frame.as_slice();
+        assert_eq!(frame.control().unwrap().frame_type(), FrameType::Auth);
+        assert_eq!(frame.algorithm().unwrap(), AuthAlgorithm::OpenSystem);
+        assert_eq!(frame.sequence().unwrap(), 1);
+        assert!(Authentication::status(frame).unwrap().is_success());
+        assert_elements(Authentication::info_elements(frame).unwrap());
+    }
+
+    #[test]
+    fn association_request() {
+        let frame = frame(ManagementBody::AssocRequest {
+            capability: 0x0001,
+            listen_interval: 10,
+            elements: elements(),
+        });
+        let frame = frame.as_slice();
+        assert_eq!(
+            frame.control().unwrap().frame_type(),
+            FrameType::AssocRequest
+        );
+        assert!(AssociationRequest::capability(frame).unwrap().has_ess());
+        assert_eq!(AssociationRequest::listen_interval(frame).unwrap(), 10);
+        assert_elements(AssociationRequest::info_elements(frame).unwrap());
+    }
+
+    #[test]
+    fn association_response() {
+        let frame = frame(ManagementBody::AssocResponse {
+            capability: 0x0001,
+            status: 17,
+            aid: 5,
+            elements: elements(),
+        });
+        let frame = frame.as_slice();
+        assert_eq!(
+            frame.control().unwrap().frame_type(),
+            FrameType::AssocResponse
+        );
+        assert!(AssociationResponse::capability(frame).unwrap().has_ess());
+        assert_eq!(AssociationResponse::status(frame).unwrap().0, 17);
+        assert_eq!(frame.aid().unwrap(), 5);
+        assert_elements(AssociationResponse::info_elements(frame).unwrap());
+    }
+
+    #[test]
+    fn deauthentication() {
+        let frame = frame(ManagementBody::Deauth { reason: 7 });
+        assert_eq!(Deauthentication::reason(frame.as_slice()).unwrap().0, 7);
+        assert_eq!(
+            Deauthentication::info_elements(frame.as_slice())
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn disassociation() {
+        let frame = frame(ManagementBody::Disassoc { reason: 8 });
+        assert_eq!(frame.control().unwrap().frame_type(), FrameType::Disassoc);
+        assert_eq!(Disassociation::reason(frame.as_slice()).unwrap().0, 8);
+    }
+
+    #[test]
+    fn action() {
+        // SA Query request with transaction ID 0x1234.
+        let frame = frame(ManagementBody::Action {
+            category: 8,
+            data: vec![0, 0x34, 0x12],
+        });
+        let action = frame.action().unwrap().unwrap();
+        assert_eq!(action.category, ActionCategory::SAQuery);
+        assert_eq!(action.code, Some(0));
+        assert!(matches!(
+            action.details,
+            ActionDetails::SAQueryRequest {
+                transaction_id: 0x1234
+            }
+        ));
+    }
+
+    #[test]
+    fn fragmented_element() {
+        let body: Vec<u8> = (0..=255).cycle().take(600).collect();
+        let elements = Elements::default()
+            .with(InfoElement::SSID(&body))
+            .with(InfoElement::DSSS(6));
+        let mut iter = InfoElementIter::new(elements.as_bytes());
+        let Some(Ok(InfoElement::Fragmented(fragmented))) = iter.next() else {
+            panic!("expected a fragmented element");
+        };
+        assert_eq!(fragmented.id(), 0);
+        assert_eq!(fragmented.body_len(), body.len());
+        let mut buf = vec![];
+        let Ok(InfoElement::SSID(ssid)) = fragmented.decode(&mut buf) else {
+            panic!("expected an SSID element");
+        };
+        assert_eq!(ssid, body.as_slice());
+        assert!(matches!(iter.next(), Some(Ok(InfoElement::DSSS(6)))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn encode_parsed_elements() {
+        let raw: &[u8] = &[
+            0, 4, b'w', b'i', b'f', b'i', // SSID
+            1, 4, 0x82, 0x84, 0x0b, 0x16, // Supported Rates
+            3, 1, 6, // DSSS
+            5, 4, 0, 1, 0, 0, // TIM
+            48, 2, 1, 0, // RSN, version only
+            221, 4, 0x00, 0x10, 0x18, 2, // Broadcom vendor element
+            255, 2, 99, 0, // unknown extension element
+            66, 1, 0, // unknown element
+        ];
+        let mut encoded = vec![];
+        for element in InfoElementIter::new(raw) {
+            element.unwrap().encode(&mut encoded);
+        }
+        assert_eq!(encoded, raw);
+    }
+}
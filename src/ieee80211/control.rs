@@ -0,0 +1,553 @@
+use super::field::{FrameControl, FrameType, MACAddr};
+use super::frame::Frame;
+use crate::misc::{slice_at, MemCast, ParseError};
+use core::mem;
+
+/// Typed view of a control frame without its FCS.
+#[derive(Debug)]
+pub enum ControlFrame<'a> {
+    RTS {
+        duration: u16,
+        ra: &'a MACAddr,
+        ta: &'a MACAddr,
+    },
+    CTS {
+        duration: u16,
+        ra: &'a MACAddr,
+    },
+    ACK {
+        duration: u16,
+        ra: &'a MACAddr,
+    },
+    CFEnd {
+        ra: &'a MACAddr,
+        bssid: &'a MACAddr,
+    },
+    PSPoll {
+        /// Association ID, without the two most significant bits.
+        aid: u16,
+        bssid: &'a MACAddr,
+        ta: &'a MACAddr,
+    },
+    BlockAckRequest(BlockAckRequest<'a>),
+    BlockAck(BlockAck<'a>),
+    Trigger(Trigger<'a>),
+    /// Control frame without a typed view.
+    Other(FrameType),
+}
+
+pub trait Control {
+    fn control_frame(&self) -> Result<ControlFrame<'_>, ParseError>;
+}
+
+impl Control for [u8] {
+    fn control_frame(&self) -> Result<ControlFrame<'_>, ParseError> {
+        let frame_type = self.control()?.frame_type();
+        let duration = u16::from_le_bytes(slice_at(self, 2, 2)?.try_into().unwrap());
+        let addr1 = || addr_at(self, ADDR1_OFFSET);
+        let addr2 = || addr_at(self, ADDR2_OFFSET);
+        Ok(match frame_type {
+            FrameType::RTS => ControlFrame::RTS {
+                duration,
+                ra: addr1()?,
+                ta: addr2()?,
+            },
+            FrameType::CTS => ControlFrame::CTS {
+                duration,
+                ra: addr1()?,
+            },
+            FrameType::ACK => ControlFrame::ACK {
+                duration,
+                ra: addr1()?,
+            },
+            FrameType::CFEnd | FrameType::CFEndAck => ControlFrame::CFEnd {
+                ra: addr1()?,
+                bssid: addr2()?,
+            },
+            FrameType::PSPoll => ControlFrame::PSPoll {
+                aid: duration & 0x3fff,
+                bssid: addr1()?,
+                ta: addr2()?,
+            },
+            FrameType::BlockAckRequest => ControlFrame::BlockAckRequest(BlockAckRequest {
+                duration,
+                ra: addr1()?,
+                ta: addr2()?,
+                control: u16::from_le_bytes(slice_at(self, BODY_OFFSET, 2)?.try_into().unwrap()),
+                info: &self[BODY_OFFSET + 2..],
+            }),
+            FrameType::BlockAck => ControlFrame::BlockAck(BlockAck {
+                duration,
+                ra: addr1()?,
+                ta: addr2()?,
+                control: u16::from_le_bytes(slice_at(self, BODY_OFFSET, 2)?.try_into().unwrap()),
+                info: &self[BODY_OFFSET + 2..],
+            }),
+            FrameType::Trigger => ControlFrame::Trigger(Trigger {
+                duration,
+                ra: addr1()?,
+                ta: addr2()?,
+                common: u64::from_le_bytes(slice_at(self, BODY_OFFSET, 8)?.try_into().unwrap()),
+                user_info: &self[BODY_OFFSET + 8..],
+            }),
+            _ => ControlFrame::Other(frame_type),
+        })
+    }
+}
+
+const ADDR1_OFFSET: usize = mem::size_of::<FrameControl>() + 2;
+const ADDR2_OFFSET: usize = ADDR1_OFFSET + mem::size_of::<MACAddr>();
+/// Start of the body of control frames with two addresses.
+const BODY_OFFSET: usize = ADDR2_OFFSET + mem::size_of::<MACAddr>();
+
+fn addr_at(frame: &[u8], offset: usize) -> Result<&MACAddr, ParseError> {
+    slice_at(frame, offset, mem::size_of::<MACAddr>())?.try_cast_ref()
+}
+
+/// Block Ack variant from the BAR and BA control fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockAckType {
+    Basic,
+    ExtendedCompressed,
+    Compressed,
+    MultiTID,
+    GCR,
+    GLKGCR,
+    MultiSTA,
+    Reserved(u8),
+}
+
+impl BlockAckType {
+    fn new(control: u16) -> Self {
+        match (control >> 1) & 0x0f {
+            0 => BlockAckType::Basic,
+            1 => BlockAckType::ExtendedCompressed,
+            2 => BlockAckType::Compressed,
+            3 => BlockAckType::MultiTID,
+            6 => BlockAckType::GCR,
+            10 => BlockAckType::GLKGCR,
+            11 => BlockAckType::MultiSTA,
+            ty => BlockAckType::Reserved(ty as u8),
+        }
+    }
+
+    /// Variants with a single starting sequence control followed by a bitmap.
+    fn is_single_tid(&self) -> bool {
+        matches!(
+            self,
+            BlockAckType::Basic | BlockAckType::ExtendedCompressed | BlockAckType::Compressed
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockAckRequest<'a> {
+    pub duration: u16,
+    pub ra: &'a MACAddr,
+    pub ta: &'a MACAddr,
+    control: u16,
+    info: &'a [u8],
+}
+
+impl BlockAckRequest<'_> {
+    pub fn ba_type(&self) -> BlockAckType {
+        BlockAckType::new(self.control)
+    }
+
+    /// The recipient does not acknowledge the request immediately.
+    pub fn is_no_ack(&self) -> bool {
+        self.control & 0x0001 != 0
+    }
+
+    pub fn tid(&self) -> u8 {
+        (self.control >> 12) as u8
+    }
+
+    /// Starting sequence number, for single-TID variants.
+    pub fn starting_sequence(&self) -> Result<Option<u16>, ParseError> {
+        if !self.ba_type().is_single_tid() {
+            return Ok(None);
+        }
+        let ssc = slice_at(self.info, 0, 2)?;
+        Ok(Some(u16::from_le_bytes([ssc[0], ssc[1]]) >> 4))
+    }
+
+    /// BAR information field, per-TID records for multi-TID variants.
+    pub fn info(&self) -> &[u8] {
+        self.info
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockAck<'a> {
+    pub duration: u16,
+    pub ra: &'a MACAddr,
+    pub ta: &'a MACAddr,
+    control: u16,
+    info: &'a [u8],
+}
+
+impl BlockAck<'_> {
+    pub fn ba_type(&self) -> BlockAckType {
+        BlockAckType::new(self.control)
+    }
+
+    pub fn is_no_ack(&self) -> bool {
+        self.control & 0x0001 != 0
+    }
+
+    pub fn tid(&self) -> u8 {
+        (self.control >> 12) as u8
+    }
+
+    /// Starting sequence number, for single-TID variants.
+    pub fn starting_sequence(&self) -> Result<Option<u16>, ParseError> {
+        if !self.ba_type().is_single_tid() {
+            return Ok(None);
+        }
+        let ssc = slice_at(self.info, 0, 2)?;
+        Ok(Some(u16::from_le_bytes([ssc[0], ssc[1]]) >> 4))
+    }
+
+    /// Block Ack bitmap, for single-TID variants. A basic bitmap has 16 bits
+    /// per MSDU, one per fragment, the compressed ones a bit per MSDU.
+    pub fn bitmap(&self) -> Result<Option<&[u8]>, ParseError> {
+        if !self.ba_type().is_single_tid() {
+            return Ok(None);
+        }
+        Ok(Some(self.info.get(2..).ok_or(ParseError::Truncated {
+            need: 2,
+            have: self.info.len(),
+        })?))
+    }
+
+    /// Whether the MSDU with sequence number `sequence` is acknowledged, or
+    /// `None` if it is outside the bitmap.
+    pub fn is_acked(&self, sequence: u16) -> Result<Option<bool>, ParseError> {
+        let (Some(start), Some(bitmap)) = (self.starting_sequence()?, self.bitmap()?) else {
+            return Ok(None);
+        };
+        let offset = (sequence.wrapping_sub(start) & 0x0fff) as usize;
+        let bit = match self.ba_type() {
+            BlockAckType::Basic => offset * 16,
+            _ => offset,
+        };
+        Ok(bitmap.get(bit / 8).map(|byte| byte & 1 << (bit % 8) != 0))
+    }
+
+    /// BA information field, per-TID or per-STA records for the other variants.
+    pub fn info(&self) -> &[u8] {
+        self.info
+    }
+}
+
+/// Trigger frame variant from the Common Info field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerType {
+    Basic,
+    BeamformingReportPoll,
+    MUBAR,
+    MURTS,
+    BufferStatusReportPoll,
+    GCRMUBAR,
+    BandwidthQueryReportPoll,
+    NDPFeedbackReportPoll,
+    Reserved(u8),
+}
+
+impl TriggerType {
+    /// Length of the Trigger Dependent User Info subfield of each User Info field.
+    fn dependent_user_info_len(&self) -> usize {
+        match self {
+            TriggerType::Basic | TriggerType::BeamformingReportPoll => 1,
+            // BAR Control and BAR Information for a compressed Block Ack.
+            TriggerType::MUBAR | TriggerType::GCRMUBAR => 4,
+            _ => 0,
+        }
+    }
+}
+
+/// HE Trigger frame.
+#[derive(Debug)]
+pub struct Trigger<'a> {
+    pub duration: u16,
+    pub ra: &'a MACAddr,
+    pub ta: &'a MACAddr,
+    common: u64,
+    user_info: &'a [u8],
+}
+
+impl<'a> Trigger<'a> {
+    pub fn trigger_type(&self) -> TriggerType {
+        match self.common & 0x0f {
+            0 => TriggerType::Basic,
+            1 => TriggerType::BeamformingReportPoll,
+            2 => TriggerType::MUBAR,
+            3 => TriggerType::MURTS,
+            4 => TriggerType::BufferStatusReportPoll,
+            5 => TriggerType::GCRMUBAR,
+            6 => TriggerType::BandwidthQueryReportPoll,
+            7 => TriggerType::NDPFeedbackReportPoll,
+            ty => TriggerType::Reserved(ty as u8),
+        }
+    }
+
+    /// Value of the L-SIG length of the solicited HE TB PPDU.
+    pub fn ul_length(&self) -> u16 {
+        ((self.common >> 4) & 0x0fff) as u16
+    }
+
+    pub fn has_more_tf(&self) -> bool {
+        self.common & 1 << 16 != 0
+    }
+
+    pub fn is_cs_required(&self) -> bool {
+        self.common & 1 << 17 != 0
+    }
+
+    pub fn ul_bandwidth_mhz(&self) -> u16 {
+        20 << ((self.common >> 18) & 0x03)
+    }
+
+    pub fn users(&self) -> TriggerUserIter<'a> {
+        TriggerUserIter {
+            buf: self.user_info,
+            dependent_len: self.trigger_type().dependent_user_info_len(),
+        }
+    }
+}
+
+/// User Info field of a Trigger frame.
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerUserInfo {
+    value: u64,
+}
+
+impl TriggerUserInfo {
+    pub fn aid12(&self) -> u16 {
+        (self.value & 0x0fff) as u16
+    }
+
+    pub fn ru_allocation(&self) -> u8 {
+        (self.value >> 12) as u8
+    }
+
+    pub fn is_ldpc(&self) -> bool {
+        self.value & 1 << 20 != 0
+    }
+
+    pub fn ul_mcs(&self) -> u8 {
+        ((self.value >> 21) & 0x0f) as u8
+    }
+
+    pub fn is_dcm(&self) -> bool {
+        self.value & 1 << 25 != 0
+    }
+
+    /// Starting spatial stream and number of spatial streams.
+    pub fn spatial_streams(&self) -> (u8, u8) {
+        let ss = (self.value >> 26) as u8 & 0x3f;
+        ((ss & 0x07) + 1, (ss >> 3) + 1)
+    }
+
+    /// Expected receive power at the AP in dBm, `None` for maximum transmit power.
+    pub fn target_rssi_dbm(&self) -> Option<i8> {
+        match (self.value >> 32) as u8 & 0x7f {
+            127 => None,
+            rssi => Some(rssi.min(90) as i8 - 110),
+        }
+    }
+}
+
+/// Iterates over the User Info fields of a Trigger frame up to the padding.
+pub struct TriggerUserIter<'a> {
+    buf: &'a [u8],
+    dependent_len: usize,
+}
+
+impl Iterator for TriggerUserIter<'_> {
+    type Item = Result<TriggerUserInfo, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        const USER_INFO_LEN: usize = 5;
+        // Padding starts with an AID12 of 4095.
+        if self.buf.len() < 2 || u16::from_le_bytes([self.buf[0], self.buf[1]]) & 0x0fff == 4095 {
+            return None;
+        }
+        let len = USER_INFO_LEN + self.dependent_len;
+        if self.buf.len() < len {
+            let have = self.buf.len();
+            self.buf = &[];
+            return Some(Err(ParseError::Truncated { need: len, have }));
+        }
+        let mut value = [0u8; 8];
+        value[..USER_INFO_LEN].copy_from_slice(&self.buf[..USER_INFO_LEN]);
+        self.buf = &self.buf[len..];
+        Some(Ok(TriggerUserInfo {
+            value: u64::from_le_bytes(value),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RA: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+    const TA: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x02];
+
+    /// Frame control, duration and the addresses of a control frame subtype.
+    fn header(sub_type: u8, duration: u16, addrs: &[[u8; 6]]) -> Vec<u8> {
+        let mut frame = vec![0x04 | sub_type << 4, 0];
+        frame.extend(duration.to_le_bytes());
+        addrs.iter().for_each(|addr| frame.extend(addr));
+        frame
+    }
+
+    #[test]
+    fn rts_cts_ack() {
+        let rts = header(11, 300, &[RA, TA]);
+        let ControlFrame::RTS { duration, ra, ta } = rts.control_frame().unwrap() else {
+            panic!("expected RTS");
+        };
+        assert_eq!((duration, ra, ta), (300, &RA.into(), &TA.into()));
+        let cts = header(12, 250, &[TA]);
+        let ControlFrame::CTS { duration, ra } = cts.control_frame().unwrap() else {
+            panic!("expected CTS");
+        };
+        assert_eq!((duration, ra), (250, &TA.into()));
+        let ack = header(13, 0, &[RA]);
+        assert!(matches!(
+            ack.control_frame().unwrap(),
+            ControlFrame::ACK { duration: 0, .. }
+        ));
+        // An RTS cut after its receiver address.
+        assert!(matches!(
+            rts[..10].control_frame(),
+            Err(ParseError::Truncated { need: 16, have: 10 })
+        ));
+    }
+
+    #[test]
+    fn ps_poll() {
+        let frame = header(10, 5 | 0xc000, &[RA, TA]);
+        let ControlFrame::PSPoll { aid, bssid, ta } = frame.control_frame().unwrap() else {
+            panic!("expected PS-Poll");
+        };
+        assert_eq!((aid, bssid, ta), (5, &RA.into(), &TA.into()));
+    }
+
+    #[test]
+    fn block_ack_request() {
+        let mut frame = header(8, 0, &[RA, TA]);
+        // Compressed BAR for TID 5, starting at sequence number 100.
+        frame.extend(0x5004u16.to_le_bytes());
+        frame.extend((100u16 << 4).to_le_bytes());
+        let ControlFrame::BlockAckRequest(bar) = frame.control_frame().unwrap() else {
+            panic!("expected Block Ack Request");
+        };
+        assert_eq!(bar.ba_type(), BlockAckType::Compressed);
+        assert_eq!(bar.tid(), 5);
+        assert!(!bar.is_no_ack());
+        assert_eq!(bar.starting_sequence().unwrap(), Some(100));
+    }
+
+    #[test]
+    fn compressed_block_ack() {
+        let mut frame = header(9, 0, &[RA, TA]);
+        frame.extend(0x5004u16.to_le_bytes());
+        frame.extend((100u16 << 4).to_le_bytes());
+        frame.extend([0b0000_0101, 0, 0, 0, 0, 0, 0, 0x80]);
+        let ControlFrame::BlockAck(ba) = frame.control_frame().unwrap() else {
+            panic!("expected Block Ack");
+        };
+        assert_eq!(ba.ba_type(), BlockAckType::Compressed);
+        assert_eq!(ba.tid(), 5);
+        assert_eq!(ba.starting_sequence().unwrap(), Some(100));
+        assert_eq!(ba.bitmap().unwrap().map(<[u8]>::len), Some(8));
+        assert_eq!(ba.is_acked(100).unwrap(), Some(true));
+        assert_eq!(ba.is_acked(101).unwrap(), Some(false));
+        assert_eq!(ba.is_acked(102).unwrap(), Some(true));
+        assert_eq!(ba.is_acked(163).unwrap(), Some(true));
+        assert_eq!(ba.is_acked(164).unwrap(), None);
+        // Sequence numbers before the window wrap around to its far end.
+        assert_eq!(ba.is_acked(99).unwrap(), None);
+    }
+
+    #[test]
+    fn basic_block_ack() {
+        let mut frame = header(9, 0, &[RA, TA]);
+        frame.extend(0x0000u16.to_le_bytes());
+        frame.extend((4094u16 << 4).to_le_bytes());
+        let mut bitmap = [0u8; 128];
+        // Fragment 0 of the MSDU following 4095, across the sequence number wrap.
+        bitmap[4] = 0x01;
+        frame.extend(bitmap);
+        let ControlFrame::BlockAck(ba) = frame.control_frame().unwrap() else {
+            panic!("expected Block Ack");
+        };
+        assert_eq!(ba.ba_type(), BlockAckType::Basic);
+        assert_eq!(ba.is_acked(4094).unwrap(), Some(false));
+        assert_eq!(ba.is_acked(0).unwrap(), Some(true));
+    }
+
+    #[test]
+    fn multi_tid_block_ack() {
+        let mut frame = header(9, 0, &[RA, TA]);
+        frame.extend(0x1006u16.to_le_bytes());
+        frame.extend([0xaa; 12]);
+        let ControlFrame::BlockAck(ba) = frame.control_frame().unwrap() else {
+            panic!("expected Block Ack");
+        };
+        assert_eq!(ba.ba_type(), BlockAckType::MultiTID);
+        assert_eq!(ba.starting_sequence().unwrap(), None);
+        assert_eq!(ba.bitmap().unwrap(), None);
+        assert_eq!(ba.info().len(), 12);
+    }
+
+    #[test]
+    fn basic_trigger() {
+        let mut frame = header(2, 100, &[RA, TA]);
+        // Basic trigger, UL length 100, more TF, 80 MHz.
+        let common: u64 = 100 << 4 | 1 << 16 | 2 << 18;
+        frame.extend(common.to_le_bytes());
+        // AID 5, RU 61, MCS 7, one stream from the first, target RSSI -50 dBm.
+        let user: u64 = 5 | 61 << 12 | 7 << 21 | 60 << 32;
+        frame.extend(&user.to_le_bytes()[..5]);
+        frame.push(0);
+        frame.extend([0xff, 0xff]);
+        let ControlFrame::Trigger(trigger) = frame.control_frame().unwrap() else {
+            panic!("expected Trigger");
+        };
+        assert_eq!(trigger.trigger_type(), TriggerType::Basic);
+        assert_eq!(trigger.ul_length(), 100);
+        assert!(trigger.has_more_tf());
+        assert!(!trigger.is_cs_required());
+        assert_eq!(trigger.ul_bandwidth_mhz(), 80);
+        let users: Vec<_> = trigger.users().collect::<Result<_, _>>().unwrap();
+        assert_eq!(users.len(), 1);
+        let user = users[0];
+        assert_eq!(user.aid12(), 5);
+        assert_eq!(user.ru_allocation(), 61);
+        assert_eq!(user.ul_mcs(), 7);
+        assert_eq!(user.spatial_streams(), (1, 1));
+        assert_eq!(user.target_rssi_dbm(), Some(-50));
+    }
+
+    #[test]
+    fn truncated_trigger_user_info() {
+        let mut frame = header(2, 0, &[RA, TA]);
+        // MU-BAR, whose user info carries a 4-byte BAR.
+        frame.extend(2u64.to_le_bytes());
+        frame.extend([5, 0, 0, 0, 0, 0]);
+        let ControlFrame::Trigger(trigger) = frame.control_frame().unwrap() else {
+            panic!("expected Trigger");
+        };
+        let mut users = trigger.users();
+        assert!(matches!(
+            users.next(),
+            Some(Err(ParseError::Truncated { need: 9, have: 6 }))
+        ));
+        assert!(users.next().is_none());
+    }
+}
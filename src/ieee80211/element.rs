@@ -1,28 +1,464 @@
+use super::bss::{APChannelReport, BSSLoad, ExtendedCapabilities, TIM};
+use super::field::MACAddr;
+use super::phy::{
+    EHTCapabilities, EHTOperation, HECapabilities, HEOperation, HTCapabilities, HTOperation,
+    VHTCapabilities, VHTOperation,
+};
+use super::regulatory::{
+    ChannelSwitch, ChannelSwitchAnnouncement, Country, TPCReport, TransmitPowerEnvelope,
+    WideBandwidthChannelSwitch,
+};
+use super::rsn::RSN;
+use super::vendor::{self, OWETransition, VendorElement, WiFiAware, P2P, WMM, WPS};
+use crate::misc::{MemCast, ParseError};
+use core::fmt::{self, Display, Formatter};
+
 #[non_exhaustive]
 #[repr(u8)]
 pub enum InfoElementId {
     SSID = 0,
-    // SupportedRates = 1,
+    SupportedRates = 1,
     // FH = 2,
     DSSS = 3,
     // ...
-    // RSN = 48,
+    TIM = 5,
+    // ...
+    Country = 7,
+    // ...
+    BSSLoad = 11,
+    // ...
+    PowerConstraint = 32,
+    // ...
+    TPCReport = 35,
+    // ...
+    ChannelSwitch = 37,
+    // ...
+    HTCapabilities = 45,
+    // ...
+    RSN = 48,
+    // ...
+    ExtendedSupportedRates = 50,
+    APChannelReport = 51,
+    NeighborReport = 52,
     // ...
-    // VendorSpecific = 221,
+    ExtendedChannelSwitch = 60,
+    HTOperation = 61,
+    // ...
+    ExtendedCapabilities = 127,
+    // ...
+    VHTCapabilities = 191,
+    VHTOperation = 192,
+    // ...
+    WideBandwidthChannelSwitch = 194,
+    TransmitPowerEnvelope = 195,
+    // ...
+    VendorSpecific = 221,
+    // ...
+    Fragment = 242,
+    // ...
+    Extension = 255,
+}
+
+/// Extension ID, the first body byte of an Element ID Extension element.
+#[non_exhaustive]
+#[repr(u8)]
+pub enum InfoElementExtId {
+    HECapabilities = 35,
+    HEOperation = 36,
+    // ...
+    EHTOperation = 106,
+    // ...
+    EHTCapabilities = 108,
 }
 
 #[non_exhaustive]
 pub enum InfoElement<'a> {
     Generic(GenericInfoElement<'a>),
     SSID(&'a [u8]),
+    SupportedRates(Rates<'a>),
     DSSS(u8),
+    TIM(TIM<'a>),
+    Country(Country<'a>),
+    BSSLoad(BSSLoad<'a>),
+    /// Local power constraint in dB, subtracted from the regulatory maximum.
+    PowerConstraint(u8),
+    TPCReport(TPCReport<'a>),
+    ChannelSwitch(ChannelSwitchAnnouncement<'a>),
+    ExtendedSupportedRates(Rates<'a>),
+    HTCapabilities(HTCapabilities<'a>),
+    RSN(RSN<'a>),
+    APChannelReport(APChannelReport<'a>),
+    NeighborReport(NeighborReport<'a>),
+    ExtendedChannelSwitch(ChannelSwitchAnnouncement<'a>),
+    HTOperation(HTOperation<'a>),
+    ExtendedCapabilities(ExtendedCapabilities<'a>),
+    VHTCapabilities(VHTCapabilities<'a>),
+    VHTOperation(VHTOperation<'a>),
+    WideBandwidthChannelSwitch(WideBandwidthChannelSwitch<'a>),
+    TransmitPowerEnvelope(TransmitPowerEnvelope<'a>),
+    /// Pre-RSN WPA vendor element.
+    WPA(RSN<'a>),
+    WMM(WMM<'a>),
+    WPS(WPS<'a>),
+    P2P(P2P<'a>),
+    WiFiAware(WiFiAware<'a>),
+    OWETransition(OWETransition<'a>),
+    /// Vendor-specific element without a typed view.
+    VendorSpecific(VendorElement<'a>),
+    HECapabilities(HECapabilities<'a>),
+    HEOperation(HEOperation<'a>),
+    EHTOperation(EHTOperation<'a>),
+    EHTCapabilities(EHTCapabilities<'a>),
+    /// Element continued in Fragment elements, see [`Fragmented::decode`].
+    Fragmented(Fragmented<'a>),
+}
+
+impl InfoElement<'_> {
+    pub fn id(&self) -> u8 {
+        match self {
+            InfoElement::Generic(element) => element.id,
+            InfoElement::Fragmented(element) => element.id(),
+            InfoElement::SSID(_) => InfoElementId::SSID as u8,
+            InfoElement::SupportedRates(_) => InfoElementId::SupportedRates as u8,
+            InfoElement::DSSS(_) => InfoElementId::DSSS as u8,
+            InfoElement::TIM(_) => InfoElementId::TIM as u8,
+            InfoElement::Country(_) => InfoElementId::Country as u8,
+            InfoElement::BSSLoad(_) => InfoElementId::BSSLoad as u8,
+            InfoElement::PowerConstraint(_) => InfoElementId::PowerConstraint as u8,
+            InfoElement::TPCReport(_) => InfoElementId::TPCReport as u8,
+            InfoElement::ChannelSwitch(_) => InfoElementId::ChannelSwitch as u8,
+            InfoElement::ExtendedSupportedRates(_) => InfoElementId::ExtendedSupportedRates as u8,
+            InfoElement::RSN(_) => InfoElementId::RSN as u8,
+            InfoElement::APChannelReport(_) => InfoElementId::APChannelReport as u8,
+            InfoElement::NeighborReport(_) => InfoElementId::NeighborReport as u8,
+            InfoElement::ExtendedChannelSwitch(_) => InfoElementId::ExtendedChannelSwitch as u8,
+            InfoElement::HTCapabilities(_) => InfoElementId::HTCapabilities as u8,
+            InfoElement::HTOperation(_) => InfoElementId::HTOperation as u8,
+            InfoElement::ExtendedCapabilities(_) => InfoElementId::ExtendedCapabilities as u8,
+            InfoElement::VHTCapabilities(_) => InfoElementId::VHTCapabilities as u8,
+            InfoElement::VHTOperation(_) => InfoElementId::VHTOperation as u8,
+            InfoElement::WideBandwidthChannelSwitch(_) => {
+                InfoElementId::WideBandwidthChannelSwitch as u8
+            }
+            InfoElement::TransmitPowerEnvelope(_) => InfoElementId::TransmitPowerEnvelope as u8,
+            InfoElement::WPA(_)
+            | InfoElement::WMM(_)
+            | InfoElement::WPS(_)
+            | InfoElement::P2P(_)
+            | InfoElement::WiFiAware(_)
+            | InfoElement::OWETransition(_)
+            | InfoElement::VendorSpecific(_) => InfoElementId::VendorSpecific as u8,
+            InfoElement::HECapabilities(_)
+            | InfoElement::HEOperation(_)
+            | InfoElement::EHTOperation(_)
+            | InfoElement::EHTCapabilities(_) => InfoElementId::Extension as u8,
+        }
+    }
+
+    /// Extension ID of Element ID Extension elements.
+    pub fn ext_id(&self) -> Option<u8> {
+        let ext_id = match self {
+            InfoElement::HECapabilities(_) => InfoElementExtId::HECapabilities,
+            InfoElement::HEOperation(_) => InfoElementExtId::HEOperation,
+            InfoElement::EHTOperation(_) => InfoElementExtId::EHTOperation,
+            InfoElement::EHTCapabilities(_) => InfoElementExtId::EHTCapabilities,
+            InfoElement::Generic(element) => return element.ext_id,
+            InfoElement::Fragmented(element) => return element.ext_id(),
+            _ => return None,
+        };
+        Some(ext_id as u8)
+    }
+
+    /// Appends the element to `buf`, followed by Fragment elements if the body
+    /// is longer than 255 bytes.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        let data = match self {
+            InfoElement::Fragmented(element) => return buf.extend_from_slice(element.raw),
+            InfoElement::Generic(element) => element.data,
+            InfoElement::SSID(ssid) => ssid,
+            InfoElement::SupportedRates(rates) | InfoElement::ExtendedSupportedRates(rates) => {
+                rates.data
+            }
+            InfoElement::DSSS(channel) => core::slice::from_ref(channel),
+            InfoElement::TIM(tim) => tim.data,
+            InfoElement::Country(country) => country.data,
+            InfoElement::BSSLoad(load) => load.data,
+            InfoElement::PowerConstraint(constraint) => core::slice::from_ref(constraint),
+            InfoElement::TPCReport(report) => report.data,
+            InfoElement::ChannelSwitch(announcement)
+            | InfoElement::ExtendedChannelSwitch(announcement) => announcement.data,
+            InfoElement::WideBandwidthChannelSwitch(switch) => switch.data,
+            InfoElement::TransmitPowerEnvelope(envelope) => envelope.data,
+            InfoElement::RSN(rsn) | InfoElement::WPA(rsn) => rsn.data,
+            InfoElement::APChannelReport(report) => report.data,
+            InfoElement::NeighborReport(report) => report.data,
+            InfoElement::ExtendedCapabilities(caps) => caps.data,
+            InfoElement::WMM(wmm) => wmm.data,
+            InfoElement::WPS(wps) => wps.data,
+            InfoElement::P2P(p2p) => p2p.data,
+            InfoElement::WiFiAware(aware) => aware.data,
+            InfoElement::OWETransition(owe) => owe.data,
+            InfoElement::VendorSpecific(element) => element.data,
+            InfoElement::HTCapabilities(caps) => caps.data,
+            InfoElement::HTOperation(operation) => operation.data,
+            InfoElement::VHTCapabilities(caps) => caps.data,
+            InfoElement::VHTOperation(operation) => operation.data,
+            InfoElement::HECapabilities(caps) => caps.data,
+            InfoElement::HEOperation(operation) => operation.data,
+            InfoElement::EHTOperation(operation) => operation.data,
+            InfoElement::EHTCapabilities(caps) => caps.data,
+        };
+        let ext_id = self.ext_id();
+        let ext_len = ext_id.is_some() as usize;
+        let (head, mut rest) = data.split_at(data.len().min(MAX_BODY_LEN - ext_len));
+        buf.push(self.id());
+        buf.push((head.len() + ext_len) as u8);
+        buf.extend(ext_id);
+        buf.extend_from_slice(head);
+        while !rest.is_empty() {
+            let (fragment, next) = rest.split_at(rest.len().min(MAX_BODY_LEN));
+            buf.push(InfoElementId::Fragment as u8);
+            buf.push(fragment.len() as u8);
+            buf.extend_from_slice(fragment);
+            rest = next;
+        }
+    }
 }
 
+const MAX_BODY_LEN: usize = 255;
+
+/// Element without a typed view.
 pub struct GenericInfoElement<'a> {
-    _id: u8,
-    _data: &'a [u8],
+    id: u8,
+    ext_id: Option<u8>,
+    data: &'a [u8],
 }
 
+impl<'a> GenericInfoElement<'a> {
+    pub fn new(id: u8, data: &'a [u8]) -> Self {
+        Self {
+            id,
+            ext_id: None,
+            data,
+        }
+    }
+
+    /// Element ID Extension element.
+    pub fn new_ext(ext_id: u8, data: &'a [u8]) -> Self {
+        Self {
+            id: InfoElementId::Extension as u8,
+            ext_id: Some(ext_id),
+            data,
+        }
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    pub fn ext_id(&self) -> Option<u8> {
+        self.ext_id
+    }
+
+    /// Body, after the extension ID of extension elements.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Element longer than 255 bytes, as received: the first element with a full
+/// body and the Fragment elements carrying the rest.
+pub struct Fragmented<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> Fragmented<'a> {
+    pub fn id(&self) -> u8 {
+        self.raw[0]
+    }
+
+    pub fn ext_id(&self) -> Option<u8> {
+        (self.id() == InfoElementId::Extension as u8).then(|| self.raw[2])
+    }
+
+    /// Bodies of the first element and of each fragment.
+    fn fragments(&self) -> impl Iterator<Item = &'a [u8]> {
+        let mut buf = self.raw;
+        core::iter::from_fn(move || {
+            let (len, rest) = match buf {
+                [_, len, rest @ ..] => (*len as usize, rest),
+                _ => return None,
+            };
+            let (body, next) = rest.split_at(len);
+            buf = next;
+            Some(body)
+        })
+    }
+
+    /// Length of the reassembled body.
+    pub fn body_len(&self) -> usize {
+        self.fragments().map(<[u8]>::len).sum()
+    }
+
+    /// Reassembles the body into `buf` and decodes it.
+    pub fn decode<'b>(&self, buf: &'b mut Vec<u8>) -> Result<InfoElement<'b>, ParseError> {
+        buf.clear();
+        self.fragments()
+            .for_each(|body| buf.extend_from_slice(body));
+        parse_element(self.id(), buf)
+    }
+}
+
+/// Body of the Supported Rates and Extended Supported Rates elements.
+pub struct Rates<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Rates<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        (!data.is_empty()).then_some(Self { data })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = SupportedRate> + 'a {
+        self.data.iter().map(|&v| SupportedRate::new(v))
+    }
+
+    /// Rates of the basic rate set, without membership selectors.
+    pub fn basic(&self) -> impl Iterator<Item = Rate> + 'a {
+        self.iter().filter_map(|rate| match rate {
+            SupportedRate::Rate { rate, basic: true } => Some(rate),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportedRate {
+    Rate {
+        rate: Rate,
+        basic: bool,
+    },
+    /// Feature a station must support to join the BSS, always flagged basic.
+    Selector(MembershipSelector),
+}
+
+impl SupportedRate {
+    fn new(v: u8) -> Self {
+        let basic = v & 0x80 != 0;
+        let selector = match v {
+            0xff => Some(MembershipSelector::HTPHY),
+            0xfe => Some(MembershipSelector::VHTPHY),
+            0xfd => Some(MembershipSelector::GLK),
+            0xfc => Some(MembershipSelector::EPD),
+            0xfb => Some(MembershipSelector::SAEHashToElement),
+            0xfa => Some(MembershipSelector::HEPHY),
+            _ => None,
+        };
+        match selector {
+            Some(selector) => SupportedRate::Selector(selector),
+            None => SupportedRate::Rate {
+                rate: Rate(v & 0x7f),
+                basic,
+            },
+        }
+    }
+}
+
+/// Data rate in units of 500 kb/s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rate(pub u8);
+
+impl Rate {
+    pub fn kbps(&self) -> u32 {
+        self.0 as u32 * 500
+    }
+
+    /// 1, 2, 5.5 and 11 Mb/s, the 802.11b DSSS and CCK rates.
+    pub fn is_dsss(&self) -> bool {
+        matches!(self.0, 2 | 4 | 11 | 22)
+    }
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 % 2 {
+            0 => write!(f, "{}", self.0 / 2),
+            _ => write!(f, "{}.5", self.0 / 2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipSelector {
+    HTPHY,
+    VHTPHY,
+    GLK,
+    /// EtherType protocol discrimination for MSDUs.
+    EPD,
+    /// SAE with the hash-to-element password element derivation only.
+    SAEHashToElement,
+    HEPHY,
+}
+
+/// Neighbor report, the candidate entry of BSS transition and neighbor report frames.
+pub struct NeighborReport<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> NeighborReport<'a> {
+    const FIXED_LEN: usize = 13;
+
+    fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= Self::FIXED_LEN).then_some(Self { data })
+    }
+
+    pub fn bssid(&self) -> &'a MACAddr {
+        self.data[..6].cast_ref()
+    }
+
+    pub fn bssid_info(&self) -> u32 {
+        u32::from_le_bytes(self.data[6..10].try_into().unwrap())
+    }
+
+    /// The AP is known to be reachable.
+    pub fn is_reachable(&self) -> bool {
+        self.bssid_info() & 0x0003 == 0x0003
+    }
+
+    pub fn operating_class(&self) -> u8 {
+        self.data[10]
+    }
+
+    pub fn channel(&self) -> u8 {
+        self.data[11]
+    }
+
+    pub fn phy_type(&self) -> u8 {
+        self.data[12]
+    }
+
+    /// BSS transition candidate preference, 255 being the most preferred.
+    pub fn preference(&self) -> Option<u8> {
+        self.subelements()
+            .find(|&(id, _)| id == 3)
+            .and_then(|(_, data)| data.first().copied())
+    }
+
+    /// Optional subelements as ID and body, stopping at the first truncated one.
+    pub fn subelements(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        let mut buf = &self.data[Self::FIXED_LEN..];
+        core::iter::from_fn(move || match *buf {
+            [id, len, ref rest @ ..] if rest.len() >= len as usize => {
+                let (data, next) = rest.split_at(len as usize);
+                buf = next;
+                Some((id, data))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct InfoElementIter<'a> {
     buf: &'a [u8],
 }
@@ -34,26 +470,190 @@ impl<'a> InfoElementIter<'a> {
 }
 
 impl<'a> Iterator for InfoElementIter<'a> {
-    type Item = InfoElement<'a>;
+    type Item = Result<InfoElement<'a>, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.buf.len() < 2 {
-            return None;
-        }
-        let id = self.buf[0];
-        let data_len = self.buf[1] as usize;
+        let (id, data_len) = match *self.buf {
+            [] => return None,
+            [id] => {
+                self.buf = &[];
+                return Some(Err(ParseError::ElementOverrun { id, len: 0 }));
+            }
+            [id, len, ..] => (id, len as usize),
+        };
         let element_len = 2 + data_len;
         if self.buf.len() < element_len {
-            return None;
+            self.buf = &[];
+            return Some(Err(ParseError::ElementOverrun { id, len: data_len }));
         }
         let data = &self.buf[2..element_len];
+        if data_len == MAX_BODY_LEN {
+            let mut raw_len = element_len;
+            // A full element is continued by the Fragment elements right after it.
+            while let [fragment_id, len, ..] = self.buf[raw_len..] {
+                if fragment_id != InfoElementId::Fragment as u8 {
+                    break;
+                }
+                let fragment_len = 2 + len as usize;
+                if self.buf.len() < raw_len + fragment_len {
+                    self.buf = &[];
+                    return Some(Err(ParseError::ElementOverrun {
+                        id: fragment_id,
+                        len: len as usize,
+                    }));
+                }
+                raw_len += fragment_len;
+                if (len as usize) < MAX_BODY_LEN {
+                    break;
+                }
+            }
+            if raw_len > element_len {
+                let raw = &self.buf[..raw_len];
+                self.buf = &self.buf[raw_len..];
+                return Some(Ok(InfoElement::Fragmented(Fragmented { raw })));
+            }
+        }
         self.buf = &self.buf[element_len..];
-        use InfoElement as IE;
-        use InfoElementId as Id;
-        match id {
-            x if x == Id::SSID as u8 => Some(IE::SSID(data)),
-            x if x == Id::DSSS as u8 => Some(IE::DSSS(data[0])),
-            _ => Some(InfoElement::Generic(GenericInfoElement { _id: id, _data: data })),
+        Some(parse_element(id, data))
+    }
+}
+
+/// Decodes an element body, dispatching extension elements on their extension ID.
+fn parse_element(id: u8, data: &[u8]) -> Result<InfoElement<'_>, ParseError> {
+    use InfoElement as IE;
+    use InfoElementId as Id;
+    let invalid = ParseError::InvalidElement { id };
+    match id {
+        x if x == Id::SSID as u8 => Ok(IE::SSID(data)),
+        x if x == Id::SupportedRates as u8 => {
+            Rates::new(data).map(IE::SupportedRates).ok_or(invalid)
+        }
+        x if x == Id::DSSS as u8 => data.first().map(|&ch| IE::DSSS(ch)).ok_or(invalid),
+        x if x == Id::ExtendedSupportedRates as u8 => Rates::new(data)
+            .map(IE::ExtendedSupportedRates)
+            .ok_or(invalid),
+        x if x == Id::TIM as u8 => TIM::new(data).map(IE::TIM).ok_or(invalid),
+        x if x == Id::BSSLoad as u8 => BSSLoad::new(data).map(IE::BSSLoad).ok_or(invalid),
+        x if x == Id::APChannelReport as u8 => APChannelReport::new(data)
+            .map(IE::APChannelReport)
+            .ok_or(invalid),
+        x if x == Id::ExtendedCapabilities as u8 => {
+            Ok(IE::ExtendedCapabilities(ExtendedCapabilities::new(data)))
+        }
+        x if x == Id::Country as u8 => Country::new(data).map(IE::Country).ok_or(invalid),
+        x if x == Id::PowerConstraint as u8 => data
+            .first()
+            .map(|&constraint| IE::PowerConstraint(constraint))
+            .ok_or(invalid),
+        x if x == Id::TPCReport as u8 => TPCReport::new(data).map(IE::TPCReport).ok_or(invalid),
+        x if x == Id::ChannelSwitch as u8 => ChannelSwitch::new(data)
+            .map(|switch| IE::ChannelSwitch(ChannelSwitchAnnouncement { data, switch }))
+            .map_err(|_| invalid),
+        x if x == Id::ExtendedChannelSwitch as u8 => ChannelSwitch::new_extended(data)
+            .map(|switch| IE::ExtendedChannelSwitch(ChannelSwitchAnnouncement { data, switch }))
+            .map_err(|_| invalid),
+        x if x == Id::WideBandwidthChannelSwitch as u8 => WideBandwidthChannelSwitch::new(data)
+            .map(IE::WideBandwidthChannelSwitch)
+            .ok_or(invalid),
+        x if x == Id::TransmitPowerEnvelope as u8 => TransmitPowerEnvelope::new(data)
+            .map(IE::TransmitPowerEnvelope)
+            .ok_or(invalid),
+        x if x == Id::RSN as u8 => RSN::parse(data).map(IE::RSN),
+        x if x == Id::VendorSpecific as u8 => vendor::parse(data),
+        x if x == Id::NeighborReport as u8 => NeighborReport::new(data)
+            .map(IE::NeighborReport)
+            .ok_or(invalid),
+        x if x == Id::HTCapabilities as u8 => HTCapabilities::new(data)
+            .map(IE::HTCapabilities)
+            .ok_or(invalid),
+        x if x == Id::HTOperation as u8 => {
+            HTOperation::new(data).map(IE::HTOperation).ok_or(invalid)
         }
+        x if x == Id::VHTCapabilities as u8 => VHTCapabilities::new(data)
+            .map(IE::VHTCapabilities)
+            .ok_or(invalid),
+        x if x == Id::VHTOperation as u8 => {
+            VHTOperation::new(data).map(IE::VHTOperation).ok_or(invalid)
+        }
+        x if x == Id::Extension as u8 => parse_extension(data),
+        _ => Ok(IE::Generic(GenericInfoElement::new(id, data))),
+    }
+}
+
+fn parse_extension(data: &[u8]) -> Result<InfoElement<'_>, ParseError> {
+    use InfoElement as IE;
+    use InfoElementExtId as ExtId;
+    let invalid = ParseError::InvalidElement {
+        id: InfoElementId::Extension as u8,
+    };
+    let (&ext_id, body) = data.split_first().ok_or(invalid)?;
+    match ext_id {
+        x if x == ExtId::HECapabilities as u8 => HECapabilities::new(body)
+            .map(IE::HECapabilities)
+            .ok_or(invalid),
+        x if x == ExtId::HEOperation as u8 => {
+            HEOperation::new(body).map(IE::HEOperation).ok_or(invalid)
+        }
+        x if x == ExtId::EHTOperation as u8 => {
+            EHTOperation::new(body).map(IE::EHTOperation).ok_or(invalid)
+        }
+        x if x == ExtId::EHTCapabilities as u8 => EHTCapabilities::new(body)
+            .map(IE::EHTCapabilities)
+            .ok_or(invalid),
+        _ => Ok(IE::Generic(GenericInfoElement::new_ext(ext_id, body))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn element_overrun() {
+        // SSID claims 8 bytes with only 2 left, so nothing after it is read.
+        let mut iter = InfoElementIter::new(&[3, 1, 6, 0, 8, b'a', b'b']);
+        assert!(matches!(iter.next(), Some(Ok(InfoElement::DSSS(6)))));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseError::ElementOverrun { id: 0, len: 8 }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn lone_element_id() {
+        let mut iter = InfoElementIter::new(&[0, 0, 48]);
+        assert!(matches!(iter.next(), Some(Ok(InfoElement::SSID(&[])))));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseError::ElementOverrun { id: 48, len: 0 }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn empty_dsss() {
+        // An invalid element does not stop the elements after it.
+        let mut iter = InfoElementIter::new(&[3, 0, 0, 1, b'a']);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseError::InvalidElement { id: 3 }))
+        ));
+        assert!(matches!(iter.next(), Some(Ok(InfoElement::SSID(b"a")))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn truncated_fragment() {
+        // A full SSID continued by a Fragment element that overruns the buffer.
+        let mut buf = vec![0, 255];
+        buf.extend([b'a'; 255]);
+        buf.extend([242, 10, b'b']);
+        let mut iter = InfoElementIter::new(&buf);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseError::ElementOverrun { id: 242, len: 10 }))
+        ));
+        assert!(iter.next().is_none());
     }
 }
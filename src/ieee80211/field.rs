@@ -4,27 +4,93 @@ use core::fmt::{self, Display, Formatter};
 pub struct FrameControl([u8; 2]);
 
 impl FrameControl {
-    // pub const VERSION: u16 = 0x0003;
+    pub const VERSION: u16 = 0x0003;
     pub const TYPE: u16 = 0x000c;
     pub const SUB_TYPE: u16 = 0x00f0;
-    // pub const TO_DS: u16 = 0x0100;
-    // pub const FROM_DS: u16 = 0x0200;
-    // pub const MORE_FRAGS: u16 = 0x0400;
-    // pub const RETRY: u16 = 0x0800;
-    // pub const POWER_MGMT: u16 = 0x1000;
-    // pub const MORE_DATA: u16 = 0x2000;
-    // pub const PROTECTED: u16 = 0x4000;
+    pub const TO_DS: u16 = 0x0100;
+    pub const FROM_DS: u16 = 0x0200;
+    pub const MORE_FRAGS: u16 = 0x0400;
+    pub const RETRY: u16 = 0x0800;
+    pub const POWER_MGMT: u16 = 0x1000;
+    pub const MORE_DATA: u16 = 0x2000;
+    pub const PROTECTED: u16 = 0x4000;
     pub const ORDER: u16 = 0x8000;
 
     pub const TYPE_MGMT: u16 = 0x0000;
-    pub const SUB_TYPE_BEACON: u16 = 0x0080;
+    pub const TYPE_CTRL: u16 = 0x0004;
+    pub const TYPE_DATA: u16 = 0x0008;
+    pub const TYPE_EXT: u16 = 0x000c;
+
+    pub const SUB_TYPE_ASSOC_REQ: u16 = 0x0000;
+    pub const SUB_TYPE_ASSOC_RESP: u16 = 0x0010;
+    pub const SUB_TYPE_REASSOC_REQ: u16 = 0x0020;
+    pub const SUB_TYPE_REASSOC_RESP: u16 = 0x0030;
     pub const SUB_TYPE_PROBE_REQ: u16 = 0x0040;
+    pub const SUB_TYPE_PROBE_RESP: u16 = 0x0050;
+    pub const SUB_TYPE_BEACON: u16 = 0x0080;
+    pub const SUB_TYPE_DISASSOC: u16 = 0x00a0;
+    pub const SUB_TYPE_AUTH: u16 = 0x00b0;
+    pub const SUB_TYPE_DEAUTH: u16 = 0x00c0;
+    pub const SUB_TYPE_ACTION: u16 = 0x00d0;
+    pub const SUB_TYPE_ACTION_NO_ACK: u16 = 0x00e0;
 
     #[inline]
     pub fn get(&self) -> u16 {
         u16::from_le_bytes(self.0)
     }
 
+    pub fn version(&self) -> u8 {
+        (self.get() & Self::VERSION) as u8
+    }
+
+    pub fn frame_type(&self) -> FrameType {
+        FrameType::new(self.get())
+    }
+
+    pub fn is_mgmt(&self) -> bool {
+        self.get() & Self::TYPE == Self::TYPE_MGMT
+    }
+
+    pub fn is_ctrl(&self) -> bool {
+        self.get() & Self::TYPE == Self::TYPE_CTRL
+    }
+
+    pub fn is_data(&self) -> bool {
+        self.get() & Self::TYPE == Self::TYPE_DATA
+    }
+
+    pub fn is_ext(&self) -> bool {
+        self.get() & Self::TYPE == Self::TYPE_EXT
+    }
+
+    pub fn is_to_ds(&self) -> bool {
+        self.get() & Self::TO_DS != 0
+    }
+
+    pub fn is_from_ds(&self) -> bool {
+        self.get() & Self::FROM_DS != 0
+    }
+
+    pub fn has_more_frags(&self) -> bool {
+        self.get() & Self::MORE_FRAGS != 0
+    }
+
+    pub fn is_retry(&self) -> bool {
+        self.get() & Self::RETRY != 0
+    }
+
+    pub fn is_power_mgmt(&self) -> bool {
+        self.get() & Self::POWER_MGMT != 0
+    }
+
+    pub fn has_more_data(&self) -> bool {
+        self.get() & Self::MORE_DATA != 0
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.get() & Self::PROTECTED != 0
+    }
+
     pub fn has_order(&self) -> bool {
         self.get() & Self::ORDER != 0
     }
@@ -38,6 +104,195 @@ impl FrameControl {
     }
 }
 
+/// Frame type and subtype from the frame control field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    AssocRequest,
+    AssocResponse,
+    ReassocRequest,
+    ReassocResponse,
+    ProbeRequest,
+    ProbeResponse,
+    TimingAdvertisement,
+    Beacon,
+    ATIM,
+    Disassoc,
+    Auth,
+    Deauth,
+    Action,
+    ActionNoAck,
+    Trigger,
+    TACK,
+    BeamformingReportPoll,
+    NDPAnnouncement,
+    ControlFrameExtension,
+    ControlWrapper,
+    BlockAckRequest,
+    BlockAck,
+    PSPoll,
+    RTS,
+    CTS,
+    ACK,
+    CFEnd,
+    CFEndAck,
+    Data,
+    DataCFAck,
+    DataCFPoll,
+    DataCFAckCFPoll,
+    Null,
+    CFAck,
+    CFPoll,
+    CFAckCFPoll,
+    QoSData,
+    QoSDataCFAck,
+    QoSDataCFPoll,
+    QoSDataCFAckCFPoll,
+    QoSNull,
+    QoSCFPoll,
+    QoSCFAckCFPoll,
+    DMGBeacon,
+    S1GBeacon,
+    Reserved { ty: u8, sub_type: u8 },
+}
+
+impl FrameType {
+    /// Decodes the type and subtype bits of a frame control value.
+    pub fn new(control: u16) -> Self {
+        let ty = ((control & FrameControl::TYPE) >> 2) as u8;
+        let sub_type = ((control & FrameControl::SUB_TYPE) >> 4) as u8;
+        use FrameType::*;
+        match (ty, sub_type) {
+            (0, 0) => AssocRequest,
+            (0, 1) => AssocResponse,
+            (0, 2) => ReassocRequest,
+            (0, 3) => ReassocResponse,
+            (0, 4) => ProbeRequest,
+            (0, 5) => ProbeResponse,
+            (0, 6) => TimingAdvertisement,
+            (0, 8) => Beacon,
+            (0, 9) => ATIM,
+            (0, 10) => Disassoc,
+            (0, 11) => Auth,
+            (0, 12) => Deauth,
+            (0, 13) => Action,
+            (0, 14) => ActionNoAck,
+            (1, 2) => Trigger,
+            (1, 3) => TACK,
+            (1, 4) => BeamformingReportPoll,
+            (1, 5) => NDPAnnouncement,
+            (1, 6) => ControlFrameExtension,
+            (1, 7) => ControlWrapper,
+            (1, 8) => BlockAckRequest,
+            (1, 9) => BlockAck,
+            (1, 10) => PSPoll,
+            (1, 11) => RTS,
+            (1, 12) => CTS,
+            (1, 13) => ACK,
+            (1, 14) => CFEnd,
+            (1, 15) => CFEndAck,
+            (2, 0) => Data,
+            (2, 1) => DataCFAck,
+            (2, 2) => DataCFPoll,
+            (2, 3) => DataCFAckCFPoll,
+            (2, 4) => Null,
+            (2, 5) => CFAck,
+            (2, 6) => CFPoll,
+            (2, 7) => CFAckCFPoll,
+            (2, 8) => QoSData,
+            (2, 9) => QoSDataCFAck,
+            (2, 10) => QoSDataCFPoll,
+            (2, 11) => QoSDataCFAckCFPoll,
+            (2, 12) => QoSNull,
+            (2, 14) => QoSCFPoll,
+            (2, 15) => QoSCFAckCFPoll,
+            (3, 0) => DMGBeacon,
+            (3, 1) => S1GBeacon,
+            (ty, sub_type) => Reserved { ty, sub_type },
+        }
+    }
+
+    /// Data subtypes with a QoS control field.
+    pub fn is_qos(&self) -> bool {
+        use FrameType::*;
+        matches!(
+            self,
+            QoSData
+                | QoSDataCFAck
+                | QoSDataCFPoll
+                | QoSDataCFAckCFPoll
+                | QoSNull
+                | QoSCFPoll
+                | QoSCFAckCFPoll
+        )
+    }
+
+    /// Data subtypes without a frame body.
+    pub fn is_null(&self) -> bool {
+        use FrameType::*;
+        matches!(
+            self,
+            Null | CFAck | CFPoll | CFAckCFPoll | QoSNull | QoSCFPoll | QoSCFAckCFPoll
+        )
+    }
+}
+
+impl Display for FrameType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use FrameType::*;
+        let name = match self {
+            AssocRequest => "Association Request",
+            AssocResponse => "Association Response",
+            ReassocRequest => "Reassociation Request",
+            ReassocResponse => "Reassociation Response",
+            ProbeRequest => "Probe Request",
+            ProbeResponse => "Probe Response",
+            TimingAdvertisement => "Timing Advertisement",
+            Beacon => "Beacon",
+            ATIM => "ATIM",
+            Disassoc => "Disassociation",
+            Auth => "Authentication",
+            Deauth => "Deauthentication",
+            Action => "Action",
+            ActionNoAck => "Action No Ack",
+            Trigger => "Trigger",
+            TACK => "TACK",
+            BeamformingReportPoll => "Beamforming Report Poll",
+            NDPAnnouncement => "NDP Announcement",
+            ControlFrameExtension => "Control Frame Extension",
+            ControlWrapper => "Control Wrapper",
+            BlockAckRequest => "Block Ack Request",
+            BlockAck => "Block Ack",
+            PSPoll => "PS-Poll",
+            RTS => "RTS",
+            CTS => "CTS",
+            ACK => "ACK",
+            CFEnd => "CF-End",
+            CFEndAck => "CF-End + CF-Ack",
+            Data => "Data",
+            DataCFAck => "Data + CF-Ack",
+            DataCFPoll => "Data + CF-Poll",
+            DataCFAckCFPoll => "Data + CF-Ack + CF-Poll",
+            Null => "Null",
+            CFAck => "CF-Ack",
+            CFPoll => "CF-Poll",
+            CFAckCFPoll => "CF-Ack + CF-Poll",
+            QoSData => "QoS Data",
+            QoSDataCFAck => "QoS Data + CF-Ack",
+            QoSDataCFPoll => "QoS Data + CF-Poll",
+            QoSDataCFAckCFPoll => "QoS Data + CF-Ack + CF-Poll",
+            QoSNull => "QoS Null",
+            QoSCFPoll => "QoS CF-Poll",
+            QoSCFAckCFPoll => "QoS CF-Ack + CF-Poll",
+            DMGBeacon => "DMG Beacon",
+            S1GBeacon => "S1G Beacon",
+            Reserved { ty, sub_type } => {
+                return write!(f, "Reserved ({}/{})", ty, sub_type);
+            }
+        };
+        f.write_str(name)
+    }
+}
+
 #[repr(C, packed)]
 pub struct ManagementHeader {
     pub duration: DurationId,
@@ -53,6 +308,23 @@ pub type DurationId = [u8; 2];
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct MACAddr([u8; 6]);
 
+impl MACAddr {
+    pub fn as_bytes(&self) -> &[u8; 6] {
+        &self.0
+    }
+
+    /// Multicast or broadcast address.
+    pub fn is_group(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+}
+
+impl From<[u8; 6]> for MACAddr {
+    fn from(v: [u8; 6]) -> Self {
+        MACAddr(v)
+    }
+}
+
 impl Display for MACAddr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -64,13 +336,211 @@ impl Display for MACAddr {
 }
 
 pub type SequenceControl = [u8; 2];
+
+#[repr(transparent)]
+pub struct QoSControl([u8; 2]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckPolicy {
+    Normal,
+    NoAck,
+    NoExplicitAck,
+    BlockAck,
+}
+
+impl QoSControl {
+    #[inline]
+    pub fn get(&self) -> u16 {
+        u16::from_le_bytes(self.0)
+    }
+
+    /// Traffic identifier, the user priority for EDCA.
+    pub fn tid(&self) -> u8 {
+        (self.get() & 0x000f) as u8
+    }
+
+    /// End of service period.
+    pub fn is_eosp(&self) -> bool {
+        self.get() & 0x0010 != 0
+    }
+
+    pub fn ack_policy(&self) -> AckPolicy {
+        match (self.get() >> 5) & 0x03 {
+            0 => AckPolicy::Normal,
+            1 => AckPolicy::NoAck,
+            2 => AckPolicy::NoExplicitAck,
+            _ => AckPolicy::BlockAck,
+        }
+    }
+
+    /// The frame body is an A-MSDU.
+    pub fn is_amsdu(&self) -> bool {
+        self.get() & 0x0080 != 0
+    }
+}
 pub type HTControl = [u8; 4];
 
 pub type Timestamp = [u8; 8];
 
+/// Status code of authentication and association responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusCode(pub u16);
+
+impl StatusCode {
+    pub const SUCCESS: u16 = 0;
+    pub const ANTI_CLOGGING_TOKEN_REQUIRED: u16 = 76;
+    pub const SAE_HASH_TO_ELEMENT: u16 = 126;
+    pub const SAE_PK: u16 = 127;
+
+    pub fn is_success(&self) -> bool {
+        self.0 == Self::SUCCESS
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.0 {
+            0 => "success",
+            1 => "unspecified failure",
+            10 => "cannot support all requested capabilities",
+            11 => "reassociation denied, no association exists",
+            12 => "association denied",
+            13 => "authentication algorithm not supported",
+            14 => "authentication sequence out of order",
+            15 => "challenge failure",
+            16 => "authentication timeout",
+            17 => "AP unable to handle additional STAs",
+            18 => "basic rates not supported",
+            30 => "association rejected temporarily",
+            31 => "robust management frame policy violation",
+            37 => "request declined",
+            40 => "invalid element",
+            41 => "invalid group cipher",
+            42 => "invalid pairwise cipher",
+            43 => "invalid AKMP",
+            44 => "unsupported RSNE version",
+            45 => "invalid RSNE capabilities",
+            46 => "cipher suite rejected by policy",
+            53 => "invalid PMKID",
+            76 => "anti-clogging token required",
+            77 => "finite cyclic group not supported",
+            82 => "rejected with suggested BSS transition",
+            126 => "SAE hash-to-element",
+            127 => "SAE-PK",
+            _ => return None,
+        })
+    }
+}
+
+impl Display for StatusCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", name, self.0),
+            None => write!(f, "status {}", self.0),
+        }
+    }
+}
+
+/// Reason code of deauthentication and disassociation frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReasonCode(pub u16);
+
+impl ReasonCode {
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.0 {
+            1 => "unspecified",
+            2 => "previous authentication no longer valid",
+            3 => "STA is leaving or has left",
+            4 => "inactivity",
+            5 => "AP unable to handle all associated STAs",
+            6 => "class 2 frame from nonauthenticated STA",
+            7 => "class 3 frame from nonassociated STA",
+            8 => "STA is leaving the BSS",
+            9 => "STA is not authenticated",
+            10 => "power capability unacceptable",
+            11 => "supported channels unacceptable",
+            12 => "BSS transition management",
+            13 => "invalid element",
+            14 => "MIC failure",
+            15 => "4-way handshake timeout",
+            16 => "group key handshake timeout",
+            17 => "element in 4-way handshake differs",
+            18 => "invalid group cipher",
+            19 => "invalid pairwise cipher",
+            20 => "invalid AKMP",
+            21 => "unsupported RSNE version",
+            22 => "invalid RSNE capabilities",
+            23 => "IEEE 802.1X authentication failed",
+            24 => "cipher suite rejected by policy",
+            34 => "too many unacknowledged frames",
+            _ => return None,
+        })
+    }
+}
+
+impl Display for ReasonCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", name, self.0),
+            None => write!(f, "reason {}", self.0),
+        }
+    }
+}
+
+/// Authentication algorithm number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthAlgorithm {
+    OpenSystem,
+    SharedKey,
+    FastBSSTransition,
+    SAE,
+    FILSSharedKey,
+    FILSSharedKeyPFS,
+    FILSPublicKey,
+    PASN,
+    Other(u16),
+}
+
+impl From<u16> for AuthAlgorithm {
+    fn from(v: u16) -> Self {
+        match v {
+            0 => AuthAlgorithm::OpenSystem,
+            1 => AuthAlgorithm::SharedKey,
+            2 => AuthAlgorithm::FastBSSTransition,
+            3 => AuthAlgorithm::SAE,
+            4 => AuthAlgorithm::FILSSharedKey,
+            5 => AuthAlgorithm::FILSSharedKeyPFS,
+            6 => AuthAlgorithm::FILSPublicKey,
+            7 => AuthAlgorithm::PASN,
+            v => AuthAlgorithm::Other(v),
+        }
+    }
+}
+
+impl Display for AuthAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthAlgorithm::OpenSystem => write!(f, "open system"),
+            AuthAlgorithm::SharedKey => write!(f, "shared key"),
+            AuthAlgorithm::FastBSSTransition => write!(f, "FT"),
+            AuthAlgorithm::SAE => write!(f, "SAE"),
+            AuthAlgorithm::FILSSharedKey => write!(f, "FILS shared key"),
+            AuthAlgorithm::FILSSharedKeyPFS => write!(f, "FILS shared key with PFS"),
+            AuthAlgorithm::FILSPublicKey => write!(f, "FILS public key"),
+            AuthAlgorithm::PASN => write!(f, "PASN"),
+            AuthAlgorithm::Other(v) => write!(f, "algorithm {}", v),
+        }
+    }
+}
+
 #[repr(transparent)]
 pub struct BeaconInterval([u8; 2]);
 
+impl BeaconInterval {
+    /// Interval in time units of 1024 us.
+    pub fn tu(&self) -> u16 {
+        u16::from_le_bytes(self.0)
+    }
+}
+
 #[repr(transparent)]
 pub struct Capability([u8; 2]);
 
@@ -82,4 +552,9 @@ impl Capability {
     pub fn has_ibss(&self) -> bool {
         self.0[0] & 1 << 1 != 0
     }
+
+    /// Data frames are protected, by WEP when no RSN or WPA element is present.
+    pub fn has_privacy(&self) -> bool {
+        self.0[0] & 1 << 4 != 0
+    }
 }
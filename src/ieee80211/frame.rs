@@ -1,142 +1,538 @@
+use super::action::ActionFrame;
 use super::element::InfoElementIter;
 use super::field::{
-    BeaconInterval, Capability, DurationId, FrameControl, HTControl, MACAddr, ManagementHeader,
-    SequenceControl, Timestamp,
+    AuthAlgorithm, BeaconInterval, Capability, DurationId, FrameControl, HTControl, MACAddr,
+    ManagementHeader, QoSControl, ReasonCode, SequenceControl, StatusCode, Timestamp,
 };
-use crate::misc::MemCast;
+use crate::misc::{crc32, slice_at, MemCast, ParseError};
 use core::mem;
 
 pub trait Frame {
-    fn control(&self) -> &FrameControl;
+    fn control(&self) -> Result<&FrameControl, ParseError>;
 }
 
 impl Frame for [u8] {
-    fn control(&self) -> &FrameControl {
-        self.cast_ref()
+    fn control(&self) -> Result<&FrameControl, ParseError> {
+        self.try_cast_ref()
+    }
+}
+
+/// Frame check sequence appended to a frame received with the radiotap FCS flag.
+pub trait FrameCheckSequence {
+    /// Splits a frame into the frame without its FCS and the FCS.
+    fn split_fcs(&self) -> Result<(&[u8], u32), ParseError>;
+    /// Recomputes the CRC-32 of the frame and compares it with its FCS.
+    fn has_valid_fcs(&self) -> Result<bool, ParseError>;
+}
+
+impl FrameCheckSequence for [u8] {
+    fn split_fcs(&self) -> Result<(&[u8], u32), ParseError> {
+        if self.len() < 4 {
+            return Err(ParseError::Truncated {
+                need: 4,
+                have: self.len(),
+            });
+        }
+        let (frame, fcs) = self.split_at(self.len() - 4);
+        Ok((frame, u32::from_le_bytes([fcs[0], fcs[1], fcs[2], fcs[3]])))
+    }
+
+    fn has_valid_fcs(&self) -> Result<bool, ParseError> {
+        let (frame, fcs) = self.split_fcs()?;
+        Ok(crc32(frame) == fcs)
     }
 }
 
 pub trait Management {
-    fn header(&self) -> &ManagementHeader;
-    fn duration_id(&self) -> &DurationId;
-    fn addr1(&self) -> &MACAddr;
-    fn addr2(&self) -> &MACAddr;
-    fn addr3(&self) -> &MACAddr;
-    fn seq_control(&self) -> &SequenceControl;
-    fn ht_control(&self) -> Option<&HTControl>;
-    fn size_of(&self) -> usize;
-    fn ra(&self) -> &MACAddr;
-    fn da(&self) -> &MACAddr;
-    fn sa(&self) -> &MACAddr;
-    fn ta(&self) -> &MACAddr;
-    fn bssid(&self) -> &MACAddr;
+    fn header(&self) -> Result<&ManagementHeader, ParseError>;
+    fn duration_id(&self) -> Result<&DurationId, ParseError>;
+    fn addr1(&self) -> Result<&MACAddr, ParseError>;
+    fn addr2(&self) -> Result<&MACAddr, ParseError>;
+    fn addr3(&self) -> Result<&MACAddr, ParseError>;
+    fn seq_control(&self) -> Result<&SequenceControl, ParseError>;
+    fn ht_control(&self) -> Result<Option<&HTControl>, ParseError>;
+    fn size_of(&self) -> Result<usize, ParseError>;
+    fn ra(&self) -> Result<&MACAddr, ParseError>;
+    fn da(&self) -> Result<&MACAddr, ParseError>;
+    fn sa(&self) -> Result<&MACAddr, ParseError>;
+    fn ta(&self) -> Result<&MACAddr, ParseError>;
+    fn bssid(&self) -> Result<&MACAddr, ParseError>;
 }
 
 impl Management for [u8] {
-    fn header(&self) -> &ManagementHeader {
-        self[mem::size_of::<FrameControl>()..].cast_ref()
+    fn header(&self) -> Result<&ManagementHeader, ParseError> {
+        self.get(mem::size_of::<FrameControl>()..)
+            .unwrap_or_default()
+            .try_cast_ref()
     }
 
-    fn duration_id(&self) -> &DurationId {
-        &self.header().duration
+    fn duration_id(&self) -> Result<&DurationId, ParseError> {
+        Ok(&self.header()?.duration)
     }
 
-    fn addr1(&self) -> &MACAddr {
-        &self.header().addr1
+    fn addr1(&self) -> Result<&MACAddr, ParseError> {
+        Ok(&self.header()?.addr1)
     }
 
-    fn addr2(&self) -> &MACAddr {
-        &self.header().addr2
+    fn addr2(&self) -> Result<&MACAddr, ParseError> {
+        Ok(&self.header()?.addr2)
     }
 
-    fn addr3(&self) -> &MACAddr {
-        &self.header().addr3
+    fn addr3(&self) -> Result<&MACAddr, ParseError> {
+        Ok(&self.header()?.addr3)
     }
 
-    fn seq_control(&self) -> &SequenceControl {
-        &self.header().seq_control
+    fn seq_control(&self) -> Result<&SequenceControl, ParseError> {
+        Ok(&self.header()?.seq_control)
     }
 
-    fn ht_control(&self) -> Option<&HTControl> {
-        if self.control().has_order() {
+    fn ht_control(&self) -> Result<Option<&HTControl>, ParseError> {
+        if self.control()?.has_order() {
             let offset = mem::size_of::<FrameControl>() + mem::size_of::<ManagementHeader>();
-            Some(self[offset..].cast_ref())
+            Ok(Some(self.get(offset..).unwrap_or_default().try_cast_ref()?))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn size_of(&self) -> usize {
+    fn size_of(&self) -> Result<usize, ParseError> {
         let size = mem::size_of::<FrameControl>() + mem::size_of::<ManagementHeader>();
-        if self.control().has_order() {
+        let size = if self.control()?.has_order() {
             size + mem::size_of::<HTControl>()
         } else {
             size
+        };
+        if self.len() < size {
+            return Err(ParseError::Truncated {
+                need: size,
+                have: self.len(),
+            });
         }
+        Ok(size)
     }
 
-    fn ra(&self) -> &MACAddr {
-        &self.addr1()
+    fn ra(&self) -> Result<&MACAddr, ParseError> {
+        self.addr1()
     }
 
-    fn da(&self) -> &MACAddr {
-        &self.addr1()
+    fn da(&self) -> Result<&MACAddr, ParseError> {
+        self.addr1()
     }
 
-    fn sa(&self) -> &MACAddr {
-        &self.addr2()
+    fn sa(&self) -> Result<&MACAddr, ParseError> {
+        self.addr2()
     }
 
-    fn ta(&self) -> &MACAddr {
-        &self.addr2()
+    fn ta(&self) -> Result<&MACAddr, ParseError> {
+        self.addr2()
     }
 
-    fn bssid(&self) -> &MACAddr {
-        &self.addr3()
+    fn bssid(&self) -> Result<&MACAddr, ParseError> {
+        self.addr3()
     }
 }
 
 pub trait Beacon {
-    fn timestamp(&self) -> &Timestamp;
-    fn interval(&self) -> &BeaconInterval;
-    fn capability(&self) -> &Capability;
-    fn info_elements(&self) -> InfoElementIter;
+    fn timestamp(&self) -> Result<&Timestamp, ParseError>;
+    fn interval(&self) -> Result<&BeaconInterval, ParseError>;
+    fn capability(&self) -> Result<&Capability, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
 }
 
 impl Beacon for [u8] {
-    fn timestamp(&self) -> &Timestamp {
-        let offset = Management::size_of(self);
-        self[offset..].cast_ref()
+    fn timestamp(&self) -> Result<&Timestamp, ParseError> {
+        let offset = Management::size_of(self)?;
+        self[offset..].try_cast_ref()
     }
 
-    fn interval(&self) -> &BeaconInterval {
-        let offset = Management::size_of(self) + mem::size_of::<Timestamp>();
-        self[offset..].cast_ref()
+    fn interval(&self) -> Result<&BeaconInterval, ParseError> {
+        let offset = Management::size_of(self)? + mem::size_of::<Timestamp>();
+        self.get(offset..).unwrap_or_default().try_cast_ref()
     }
 
-    fn capability(&self) -> &Capability {
-        let offset = Management::size_of(self)
+    fn capability(&self) -> Result<&Capability, ParseError> {
+        let offset = Management::size_of(self)?
             + mem::size_of::<BeaconInterval>()
             + mem::size_of::<Timestamp>();
-        self[offset..].cast_ref()
+        self.get(offset..).unwrap_or_default().try_cast_ref()
     }
 
-    fn info_elements(&self) -> InfoElementIter {
-        let offset = Management::size_of(self)
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        let offset = Management::size_of(self)?
             + mem::size_of::<BeaconInterval>()
             + mem::size_of::<Timestamp>()
             + mem::size_of::<Capability>();
-        InfoElementIter::new(&self[offset..])
+        let elements = self.get(offset..).ok_or(ParseError::Truncated {
+            need: offset,
+            have: self.len(),
+        })?;
+        Ok(InfoElementIter::new(elements))
     }
 }
 
 pub trait ProbeRequest {
-    fn info_elements(&self) -> InfoElementIter;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
 }
 
 impl ProbeRequest for [u8] {
-    fn info_elements(&self) -> InfoElementIter {
-        let offset = Management::size_of(self);
-        InfoElementIter::new(&self[offset..])
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        let offset = Management::size_of(self)?;
+        Ok(InfoElementIter::new(&self[offset..]))
+    }
+}
+
+/// Probe responses share the fixed fields of beacons.
+pub trait ProbeResponse {
+    fn timestamp(&self) -> Result<&Timestamp, ParseError>;
+    fn interval(&self) -> Result<&BeaconInterval, ParseError>;
+    fn capability(&self) -> Result<&Capability, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl ProbeResponse for [u8] {
+    fn timestamp(&self) -> Result<&Timestamp, ParseError> {
+        Beacon::timestamp(self)
+    }
+
+    fn interval(&self) -> Result<&BeaconInterval, ParseError> {
+        Beacon::interval(self)
+    }
+
+    fn capability(&self) -> Result<&Capability, ParseError> {
+        Beacon::capability(self)
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        Beacon::info_elements(self)
+    }
+}
+
+pub trait Authentication {
+    fn algorithm(&self) -> Result<AuthAlgorithm, ParseError>;
+    /// Authentication transaction sequence number.
+    fn sequence(&self) -> Result<u16, ParseError>;
+    fn status(&self) -> Result<StatusCode, ParseError>;
+    /// Finite cyclic group of an SAE commit.
+    fn sae_group(&self) -> Result<Option<u16>, ParseError>;
+    /// Send-Confirm counter of an SAE confirm.
+    fn sae_send_confirm(&self) -> Result<Option<u16>, ParseError>;
+    /// Elements after the fixed fields, empty for SAE whose body is not made of elements.
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl Authentication for [u8] {
+    fn algorithm(&self) -> Result<AuthAlgorithm, ParseError> {
+        Ok(u16_at(self, Management::size_of(self)?)?.into())
+    }
+
+    fn sequence(&self) -> Result<u16, ParseError> {
+        u16_at(self, Management::size_of(self)? + 2)
+    }
+
+    fn status(&self) -> Result<StatusCode, ParseError> {
+        Ok(StatusCode(u16_at(self, Management::size_of(self)? + 4)?))
+    }
+
+    fn sae_group(&self) -> Result<Option<u16>, ParseError> {
+        let status = Authentication::status(self)?.0;
+        let has_group = matches!(
+            status,
+            StatusCode::SUCCESS
+                | StatusCode::ANTI_CLOGGING_TOKEN_REQUIRED
+                | StatusCode::SAE_HASH_TO_ELEMENT
+                | StatusCode::SAE_PK
+        );
+        if self.algorithm()? != AuthAlgorithm::SAE || self.sequence()? != 1 || !has_group {
+            return Ok(None);
+        }
+        Ok(Some(u16_at(self, Management::size_of(self)? + 6)?))
+    }
+
+    fn sae_send_confirm(&self) -> Result<Option<u16>, ParseError> {
+        if self.algorithm()? != AuthAlgorithm::SAE
+            || self.sequence()? != 2
+            || !Authentication::status(self)?.is_success()
+        {
+            return Ok(None);
+        }
+        Ok(Some(u16_at(self, Management::size_of(self)? + 6)?))
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        if self.algorithm()? == AuthAlgorithm::SAE {
+            return Ok(InfoElementIter::new(&[]));
+        }
+        elements_at(self, Management::size_of(self)? + 6)
+    }
+}
+
+pub trait AssociationRequest {
+    fn capability(&self) -> Result<&Capability, ParseError>;
+    fn listen_interval(&self) -> Result<u16, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl AssociationRequest for [u8] {
+    fn capability(&self) -> Result<&Capability, ParseError> {
+        slice_at(self, Management::size_of(self)?, 2)?.try_cast_ref()
+    }
+
+    fn listen_interval(&self) -> Result<u16, ParseError> {
+        u16_at(self, Management::size_of(self)? + 2)
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        elements_at(self, Management::size_of(self)? + 4)
+    }
+}
+
+/// Reassociation requests add the current AP to the association request fields.
+pub trait ReassociationRequest {
+    fn capability(&self) -> Result<&Capability, ParseError>;
+    fn listen_interval(&self) -> Result<u16, ParseError>;
+    fn current_ap(&self) -> Result<&MACAddr, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl ReassociationRequest for [u8] {
+    fn capability(&self) -> Result<&Capability, ParseError> {
+        AssociationRequest::capability(self)
+    }
+
+    fn listen_interval(&self) -> Result<u16, ParseError> {
+        AssociationRequest::listen_interval(self)
+    }
+
+    fn current_ap(&self) -> Result<&MACAddr, ParseError> {
+        let offset = Management::size_of(self)? + 4;
+        slice_at(self, offset, mem::size_of::<MACAddr>())?.try_cast_ref()
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        elements_at(
+            self,
+            Management::size_of(self)? + 4 + mem::size_of::<MACAddr>(),
+        )
+    }
+}
+
+/// Association and reassociation responses.
+pub trait AssociationResponse {
+    fn capability(&self) -> Result<&Capability, ParseError>;
+    fn status(&self) -> Result<StatusCode, ParseError>;
+    /// Association ID, without the two most significant bits.
+    fn aid(&self) -> Result<u16, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl AssociationResponse for [u8] {
+    fn capability(&self) -> Result<&Capability, ParseError> {
+        slice_at(self, Management::size_of(self)?, 2)?.try_cast_ref()
+    }
+
+    fn status(&self) -> Result<StatusCode, ParseError> {
+        Ok(StatusCode(u16_at(self, Management::size_of(self)? + 2)?))
+    }
+
+    fn aid(&self) -> Result<u16, ParseError> {
+        Ok(u16_at(self, Management::size_of(self)? + 4)? & 0x3fff)
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        elements_at(self, Management::size_of(self)? + 6)
+    }
+}
+
+pub trait Deauthentication {
+    fn reason(&self) -> Result<ReasonCode, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl Deauthentication for [u8] {
+    fn reason(&self) -> Result<ReasonCode, ParseError> {
+        Ok(ReasonCode(u16_at(self, Management::size_of(self)?)?))
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        elements_at(self, Management::size_of(self)? + 2)
+    }
+}
+
+pub trait Disassociation {
+    fn reason(&self) -> Result<ReasonCode, ParseError>;
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError>;
+}
+
+impl Disassociation for [u8] {
+    fn reason(&self) -> Result<ReasonCode, ParseError> {
+        Deauthentication::reason(self)
+    }
+
+    fn info_elements(&self) -> Result<InfoElementIter<'_>, ParseError> {
+        Deauthentication::info_elements(self)
+    }
+}
+
+/// Action and action no-ack frames.
+pub trait Action {
+    /// Category, action code and decoded fields, `None` for protected frames
+    /// whose body is encrypted.
+    fn action(&self) -> Result<Option<ActionFrame<'_>>, ParseError>;
+}
+
+impl Action for [u8] {
+    fn action(&self) -> Result<Option<ActionFrame<'_>>, ParseError> {
+        if self.control()?.is_protected() {
+            return Ok(None);
+        }
+        let body = &self[Management::size_of(self)?..];
+        ActionFrame::parse(body).map(Some)
+    }
+}
+
+fn u16_at(frame: &[u8], offset: usize) -> Result<u16, ParseError> {
+    let bytes = slice_at(frame, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn elements_at(frame: &[u8], offset: usize) -> Result<InfoElementIter<'_>, ParseError> {
+    let elements = frame.get(offset..).ok_or(ParseError::Truncated {
+        need: offset,
+        have: frame.len(),
+    })?;
+    Ok(InfoElementIter::new(elements))
+}
+
+/// Data frame accessors. Address roles depend on the ToDS and FromDS bits:
+///
+/// | ToDS | FromDS | addr1      | addr2      | addr3 | addr4 |
+/// |------|--------|------------|------------|-------|-------|
+/// | 0    | 0      | RA = DA    | TA = SA    | BSSID |       |
+/// | 0    | 1      | RA = DA    | TA = BSSID | SA    |       |
+/// | 1    | 0      | RA = BSSID | TA = SA    | DA    |       |
+/// | 1    | 1      | RA         | TA         | DA    | SA    |
+pub trait Data {
+    fn addr4(&self) -> Result<Option<&MACAddr>, ParseError>;
+    fn qos_control(&self) -> Result<Option<&QoSControl>, ParseError>;
+    fn ht_control(&self) -> Result<Option<&HTControl>, ParseError>;
+    fn size_of(&self) -> Result<usize, ParseError>;
+    fn ra(&self) -> Result<&MACAddr, ParseError>;
+    fn ta(&self) -> Result<&MACAddr, ParseError>;
+    fn da(&self) -> Result<&MACAddr, ParseError>;
+    fn sa(&self) -> Result<&MACAddr, ParseError>;
+    /// BSSID, or `None` for four-address frames.
+    fn bssid(&self) -> Result<Option<&MACAddr>, ParseError>;
+    /// Frame body after the header, encrypted if the frame is protected.
+    fn payload(&self) -> Result<&[u8], ParseError>;
+    /// Ethertype and payload after the LLC/SNAP header, or `None` if the body
+    /// is protected, an A-MSDU, or not SNAP encapsulated.
+    fn llc_snap(&self) -> Result<Option<(u16, &[u8])>, ParseError>;
+}
+
+impl Data for [u8] {
+    fn addr4(&self) -> Result<Option<&MACAddr>, ParseError> {
+        let control = self.control()?;
+        if control.is_to_ds() && control.is_from_ds() {
+            let offset = mem::size_of::<FrameControl>() + mem::size_of::<ManagementHeader>();
+            Ok(Some(self.get(offset..).unwrap_or_default().try_cast_ref()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn qos_control(&self) -> Result<Option<&QoSControl>, ParseError> {
+        if !self.control()?.frame_type().is_qos() {
+            return Ok(None);
+        }
+        let offset = mem::size_of::<FrameControl>()
+            + mem::size_of::<ManagementHeader>()
+            + self.addr4()?.map_or(0, |_| mem::size_of::<MACAddr>());
+        Ok(Some(self.get(offset..).unwrap_or_default().try_cast_ref()?))
+    }
+
+    fn ht_control(&self) -> Result<Option<&HTControl>, ParseError> {
+        // Only QoS data frames carry an HT Control field.
+        if !self.control()?.has_order() || self.qos_control()?.is_none() {
+            return Ok(None);
+        }
+        let offset = Data::size_of(self)? - mem::size_of::<HTControl>();
+        Ok(Some(self[offset..].try_cast_ref()?))
+    }
+
+    fn size_of(&self) -> Result<usize, ParseError> {
+        let control = self.control()?;
+        let mut size = mem::size_of::<FrameControl>() + mem::size_of::<ManagementHeader>();
+        if control.is_to_ds() && control.is_from_ds() {
+            size += mem::size_of::<MACAddr>();
+        }
+        if control.frame_type().is_qos() {
+            size += mem::size_of::<QoSControl>();
+            if control.has_order() {
+                size += mem::size_of::<HTControl>();
+            }
+        }
+        if self.len() < size {
+            return Err(ParseError::Truncated {
+                need: size,
+                have: self.len(),
+            });
+        }
+        Ok(size)
+    }
+
+    fn ra(&self) -> Result<&MACAddr, ParseError> {
+        Ok(&Management::header(self)?.addr1)
+    }
+
+    fn ta(&self) -> Result<&MACAddr, ParseError> {
+        Ok(&Management::header(self)?.addr2)
+    }
+
+    fn da(&self) -> Result<&MACAddr, ParseError> {
+        let header = Management::header(self)?;
+        if self.control()?.is_to_ds() {
+            Ok(&header.addr3)
+        } else {
+            Ok(&header.addr1)
+        }
+    }
+
+    fn sa(&self) -> Result<&MACAddr, ParseError> {
+        let control = self.control()?;
+        let header = Management::header(self)?;
+        match (control.is_to_ds(), control.is_from_ds()) {
+            (false, false) | (true, false) => Ok(&header.addr2),
+            (false, true) => Ok(&header.addr3),
+            (true, true) => Ok(self.addr4()?.unwrap_or(&header.addr2)),
+        }
+    }
+
+    fn bssid(&self) -> Result<Option<&MACAddr>, ParseError> {
+        let control = self.control()?;
+        let header = Management::header(self)?;
+        match (control.is_to_ds(), control.is_from_ds()) {
+            (false, false) => Ok(Some(&header.addr3)),
+            (false, true) => Ok(Some(&header.addr2)),
+            (true, false) => Ok(Some(&header.addr1)),
+            (true, true) => Ok(None),
+        }
+    }
+
+    fn payload(&self) -> Result<&[u8], ParseError> {
+        Ok(&self[Data::size_of(self)?..])
+    }
+
+    fn llc_snap(&self) -> Result<Option<(u16, &[u8])>, ParseError> {
+        let control = self.control()?;
+        let amsdu = self.qos_control()?.is_some_and(|qos| qos.is_amsdu());
+        if control.is_protected() || amsdu || control.frame_type().is_null() {
+            return Ok(None);
+        }
+        match Data::payload(self)? {
+            [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00 | 0xf8, t0, t1, payload @ ..] => {
+                Ok(Some((u16::from_be_bytes([*t0, *t1]), payload)))
+            }
+            _ => Ok(None),
+        }
     }
 }
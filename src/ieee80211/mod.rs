@@ -1,3 +1,11 @@
+pub mod action;
+pub mod bss;
+pub mod builder;
+pub mod control;
 pub mod element;
 pub mod field;
 pub mod frame;
+pub mod phy;
+pub mod regulatory;
+pub mod rsn;
+pub mod vendor;
@@ -0,0 +1,470 @@
+use super::element::{InfoElement, InfoElementIter};
+use core::fmt::{self, Display, Formatter};
+
+/// HT Capabilities element.
+pub struct HTCapabilities<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> HTCapabilities<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 26).then_some(Self { data })
+    }
+
+    pub fn info(&self) -> u16 {
+        u16::from_le_bytes([self.data[0], self.data[1]])
+    }
+
+    pub fn supports_40mhz(&self) -> bool {
+        self.info() & 0x0002 != 0
+    }
+
+    /// Supported receive MCS 0 to 76, one bit each.
+    pub fn rx_mcs_bitmask(&self) -> &'a [u8] {
+        &self.data[3..13]
+    }
+
+    /// Spatial streams of the highest supported equal modulation MCS.
+    pub fn max_spatial_streams(&self) -> u8 {
+        let streams = self.rx_mcs_bitmask()[..4].iter().rposition(|&mcs| mcs != 0);
+        streams.map_or(0, |i| i as u8 + 1)
+    }
+}
+
+/// HT Operation element.
+pub struct HTOperation<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> HTOperation<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 22).then_some(Self { data })
+    }
+
+    pub fn primary_channel(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Secondary 20 MHz channel above (1) or below (-1) the primary one, 0 if none.
+    pub fn secondary_offset(&self) -> i8 {
+        match self.data[1] & 0x03 {
+            1 => 1,
+            3 => -1,
+            _ => 0,
+        }
+    }
+
+    /// Channel of the 40 MHz channel center, if any.
+    pub fn center_channel(&self) -> Option<u8> {
+        match self.secondary_offset() {
+            0 => None,
+            offset => Some(self.primary_channel().wrapping_add_signed(2 * offset)),
+        }
+    }
+}
+
+/// Maximum spatial streams of a VHT or HE MCS map, 2 bits per stream.
+fn mcs_map_streams(map: u16) -> u8 {
+    (0..8)
+        .rev()
+        .find(|i| (map >> (2 * i)) & 0x03 != 0x03)
+        .map_or(0, |i| i + 1)
+}
+
+/// VHT Capabilities element.
+pub struct VHTCapabilities<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> VHTCapabilities<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 12).then_some(Self { data })
+    }
+
+    pub fn info(&self) -> u32 {
+        u32::from_le_bytes(self.data[..4].try_into().unwrap())
+    }
+
+    pub fn supports_160mhz(&self) -> bool {
+        (self.info() >> 2) & 0x03 != 0
+    }
+
+    pub fn rx_mcs_map(&self) -> u16 {
+        u16::from_le_bytes([self.data[4], self.data[5]])
+    }
+
+    pub fn max_spatial_streams(&self) -> u8 {
+        mcs_map_streams(self.rx_mcs_map())
+    }
+}
+
+/// VHT Operation element.
+pub struct VHTOperation<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> VHTOperation<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 5).then_some(Self { data })
+    }
+
+    /// Channel width field, 0 for 20 or 40 MHz as set by the HT Operation element.
+    pub fn channel_width(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Channel center frequency segment 0.
+    pub fn ccfs0(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// Channel center frequency segment 1.
+    pub fn ccfs1(&self) -> u8 {
+        self.data[2]
+    }
+
+    /// Width and center channels, `None` when the HT Operation element applies.
+    pub fn channel(&self) -> Option<Channel> {
+        vht_channel(self.channel_width(), self.ccfs0(), self.ccfs1())
+    }
+}
+
+/// Channel of VHT Operation style width and center segment fields, `None` for
+/// 20 or 40 MHz.
+pub(super) fn vht_channel(width: u8, ccfs0: u8, ccfs1: u8) -> Option<Channel> {
+    match width {
+        1 => Some(Channel::from_segments(80, ccfs0, ccfs1)),
+        2 => Some(Channel::new(160, ccfs0)),
+        3 => Some(Channel {
+            width_mhz: 160,
+            center: ccfs0,
+            center2: Some(ccfs1),
+        }),
+        _ => None,
+    }
+}
+
+/// HE Capabilities element, an Element ID Extension element.
+pub struct HECapabilities<'a> {
+    /// Body after the extension ID.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> HECapabilities<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 21).then_some(Self { data })
+    }
+
+    pub fn mac_info(&self) -> &'a [u8] {
+        &self.data[..6]
+    }
+
+    pub fn phy_info(&self) -> &'a [u8] {
+        &self.data[6..17]
+    }
+
+    pub fn supports_160mhz(&self) -> bool {
+        self.phy_info()[0] & 0x08 != 0
+    }
+
+    /// Receive HE-MCS map for channels up to 80 MHz.
+    pub fn rx_mcs_map(&self) -> u16 {
+        u16::from_le_bytes([self.data[17], self.data[18]])
+    }
+
+    pub fn max_spatial_streams(&self) -> u8 {
+        mcs_map_streams(self.rx_mcs_map())
+    }
+}
+
+/// HE Operation element, an Element ID Extension element.
+pub struct HEOperation<'a> {
+    /// Body after the extension ID.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> HEOperation<'a> {
+    const FIXED_LEN: usize = 6;
+
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        if data.len() < Self::FIXED_LEN {
+            return None;
+        }
+        let operation = Self { data };
+        (!operation.has_six_ghz() || data.len() >= operation.six_ghz_offset() + 5)
+            .then_some(operation)
+    }
+
+    fn parameters(&self) -> u32 {
+        u32::from_le_bytes([self.data[0], self.data[1], self.data[2], 0])
+    }
+
+    fn has_six_ghz(&self) -> bool {
+        self.parameters() & 1 << 17 != 0
+    }
+
+    fn six_ghz_offset(&self) -> usize {
+        let mut offset = Self::FIXED_LEN;
+        if self.parameters() & 1 << 14 != 0 {
+            offset += 3;
+        }
+        if self.parameters() & 1 << 15 != 0 {
+            offset += 1;
+        }
+        offset
+    }
+
+    pub fn bss_color(&self) -> u8 {
+        self.data[3] & 0x3f
+    }
+
+    pub fn is_bss_color_disabled(&self) -> bool {
+        self.data[3] & 0x80 != 0
+    }
+
+    /// Primary channel and channel of a BSS in the 6 GHz band.
+    pub fn six_ghz(&self) -> Option<(u8, Channel)> {
+        if !self.has_six_ghz() {
+            return None;
+        }
+        let info = &self.data[self.six_ghz_offset()..];
+        let width = match info[1] & 0x03 {
+            0 => 20,
+            1 => 40,
+            2 => 80,
+            _ => 160,
+        };
+        Some((info[0], Channel::from_segments(width, info[2], info[3])))
+    }
+}
+
+/// EHT Capabilities element, an Element ID Extension element.
+pub struct EHTCapabilities<'a> {
+    /// Body after the extension ID.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> EHTCapabilities<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 11).then_some(Self { data })
+    }
+
+    pub fn phy_info(&self) -> &'a [u8] {
+        &self.data[2..11]
+    }
+
+    pub fn supports_320mhz(&self) -> bool {
+        self.phy_info()[0] & 0x02 != 0
+    }
+}
+
+/// EHT Operation element, an Element ID Extension element.
+pub struct EHTOperation<'a> {
+    /// Body after the extension ID.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> EHTOperation<'a> {
+    const FIXED_LEN: usize = 5;
+
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        let operation = Self { data };
+        let len = match operation.has_info() {
+            true => Self::FIXED_LEN + 3,
+            false => Self::FIXED_LEN,
+        };
+        (data.len() >= len).then_some(operation)
+    }
+
+    fn has_info(&self) -> bool {
+        self.data.first().is_some_and(|params| params & 0x01 != 0)
+    }
+
+    /// Operating channel, `None` when the HE or VHT operation applies.
+    pub fn channel(&self) -> Option<Channel> {
+        if !self.has_info() {
+            return None;
+        }
+        let info = &self.data[Self::FIXED_LEN..];
+        let width = match info[0] & 0x07 {
+            0 => 20,
+            1 => 40,
+            2 => 80,
+            3 => 160,
+            _ => 320,
+        };
+        Some(Channel::from_segments(width, info[1], info[2]))
+    }
+}
+
+/// Width and center channel numbers of an operating channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Channel {
+    pub width_mhz: u16,
+    pub center: u8,
+    /// Center of the second segment of an 80+80 MHz channel.
+    pub center2: Option<u8>,
+}
+
+impl Channel {
+    fn new(width_mhz: u16, center: u8) -> Self {
+        Self {
+            width_mhz,
+            center,
+            center2: None,
+        }
+    }
+
+    /// Channel from center frequency segments, where segment 1 is the center
+    /// of the whole channel for 160 and 320 MHz, and of the second 80 MHz
+    /// segment if it is not adjacent.
+    fn from_segments(width_mhz: u16, ccfs0: u8, ccfs1: u8) -> Self {
+        match (width_mhz, ccfs1.abs_diff(ccfs0)) {
+            (_, _) if ccfs1 == 0 => Self::new(width_mhz, ccfs0),
+            (80 | 160, 8) => Self::new(160, ccfs1),
+            (80 | 160, _) => Self {
+                width_mhz: 160,
+                center: ccfs0,
+                center2: Some(ccfs1),
+            },
+            (320, _) => Self::new(320, ccfs1),
+            _ => Self::new(width_mhz, ccfs0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    GHz2_4,
+    GHz5,
+    GHz6,
+}
+
+impl Band {
+    pub fn channel_mhz(&self, channel: u8) -> u16 {
+        let channel = channel as u16;
+        match self {
+            Band::GHz2_4 if channel == 14 => 2484,
+            Band::GHz2_4 => 2407 + 5 * channel,
+            Band::GHz5 => 5000 + 5 * channel,
+            Band::GHz6 => 5950 + 5 * channel,
+        }
+    }
+}
+
+/// PHY standards and operating channel of a BSS, from its beacon or probe
+/// response elements.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BSSPhy {
+    pub primary_channel: Option<u8>,
+    pub band: Option<Band>,
+    /// Operating channel wider than 20 MHz.
+    pub channel: Option<Channel>,
+    pub spatial_streams: u8,
+    pub ht: bool,
+    pub vht: bool,
+    pub he: bool,
+    pub eht: bool,
+}
+
+impl BSSPhy {
+    /// Summarizes the PHY of a BSS, skipping elements that fail to decode.
+    pub fn from_elements(elements: InfoElementIter) -> Self {
+        let mut phy = Self::default();
+        let (mut ht_channel, mut vht_channel, mut he_channel, mut eht_channel) =
+            (None, None, None, None);
+        for element in elements.flatten() {
+            match element {
+                InfoElement::DSSS(channel) => phy.primary_channel = Some(channel),
+                InfoElement::HTCapabilities(caps) => {
+                    phy.ht = true;
+                    phy.spatial_streams = phy.spatial_streams.max(caps.max_spatial_streams());
+                }
+                InfoElement::HTOperation(operation) => {
+                    phy.primary_channel = Some(operation.primary_channel());
+                    ht_channel = operation
+                        .center_channel()
+                        .map(|center| Channel::new(40, center));
+                }
+                InfoElement::VHTCapabilities(caps) => {
+                    phy.vht = true;
+                    phy.spatial_streams = phy.spatial_streams.max(caps.max_spatial_streams());
+                }
+                InfoElement::VHTOperation(operation) => vht_channel = operation.channel(),
+                InfoElement::HECapabilities(caps) => {
+                    phy.he = true;
+                    phy.spatial_streams = phy.spatial_streams.max(caps.max_spatial_streams());
+                }
+                InfoElement::HEOperation(operation) => {
+                    if let Some((primary, channel)) = operation.six_ghz() {
+                        phy.band = Some(Band::GHz6);
+                        phy.primary_channel = Some(primary);
+                        he_channel = Some(channel);
+                    }
+                }
+                InfoElement::EHTCapabilities(_) => phy.eht = true,
+                InfoElement::EHTOperation(operation) => eht_channel = operation.channel(),
+                _ => (),
+            }
+        }
+        if phy.band.is_none() {
+            phy.band = phy.primary_channel.map(|channel| match channel {
+                1..=14 => Band::GHz2_4,
+                _ => Band::GHz5,
+            });
+        }
+        phy.channel = eht_channel
+            .or(he_channel)
+            .or(vht_channel)
+            .or(ht_channel)
+            .filter(|channel| channel.width_mhz > 20);
+        phy
+    }
+
+    pub fn width_mhz(&self) -> u16 {
+        self.channel.map_or(20, |channel| channel.width_mhz)
+    }
+
+    /// Center frequencies of the operating channel, the second one for 80+80 MHz.
+    pub fn center_mhz(&self) -> Option<(u16, Option<u16>)> {
+        let band = self.band?;
+        let channel = self.channel?;
+        let center2 = channel.center2.map(|center| band.channel_mhz(center));
+        Some((band.channel_mhz(channel.center), center2))
+    }
+}
+
+/// Formats as `channel: 36, 80 MHz at 5210 MHz, 2 streams, 802.11n/ac/ax`.
+impl Display for BSSPhy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        if let Some(channel) = self.primary_channel {
+            write!(f, "channel: {}", channel)?;
+            sep = ", ";
+        }
+        if let Some((center, center2)) = self.center_mhz() {
+            match center2 {
+                Some(center2) => write!(f, "{}80+80 MHz at {}/{} MHz", sep, center, center2)?,
+                None => write!(f, "{}{} MHz at {} MHz", sep, self.width_mhz(), center)?,
+            }
+            sep = ", ";
+        }
+        if self.spatial_streams > 0 {
+            write!(f, "{}{} streams", sep, self.spatial_streams)?;
+            sep = ", ";
+        }
+        let standards: Vec<_> = [
+            (self.ht, "n"),
+            (self.vht, "ac"),
+            (self.he, "ax"),
+            (self.eht, "be"),
+        ]
+        .iter()
+        .filter_map(|&(supported, name)| supported.then_some(name))
+        .collect();
+        if !standards.is_empty() {
+            write!(f, "{}802.11{}", sep, standards.join("/"))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use super::element::InfoElementId;
+use super::phy::{vht_channel, Channel};
+use crate::misc::{slice_at, ParseError};
+use core::fmt::{self, Display, Formatter};
+
+/// Environment, the third byte of the country string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountryEnvironment {
+    Any,
+    Outdoor,
+    Indoor,
+    /// Non-country entity, such as ships or aircraft.
+    NonCountry,
+    /// Operating classes are from the global table.
+    GlobalOperatingClasses,
+    Other(u8),
+}
+
+impl From<u8> for CountryEnvironment {
+    fn from(value: u8) -> Self {
+        match value {
+            b' ' => CountryEnvironment::Any,
+            b'O' => CountryEnvironment::Outdoor,
+            b'I' => CountryEnvironment::Indoor,
+            b'X' => CountryEnvironment::NonCountry,
+            0x04 => CountryEnvironment::GlobalOperatingClasses,
+            _ => CountryEnvironment::Other(value),
+        }
+    }
+}
+
+impl Display for CountryEnvironment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CountryEnvironment::Any => "any",
+            CountryEnvironment::Outdoor => "outdoor",
+            CountryEnvironment::Indoor => "indoor",
+            CountryEnvironment::NonCountry => "non-country",
+            CountryEnvironment::GlobalOperatingClasses => "global classes",
+            CountryEnvironment::Other(value) => return write!(f, "environment {:#04x}", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Triplet of the Country element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountryTriplet {
+    /// Channels `first_channel` onwards, 1 apart in 2.4 GHz and 4 apart above.
+    SubBand {
+        first_channel: u8,
+        channels: u8,
+        max_power_dbm: i8,
+    },
+    /// Starts the subbands of an operating class, `extension_id` being 201 or more.
+    OperatingClass {
+        extension_id: u8,
+        operating_class: u8,
+        coverage_class: u8,
+    },
+}
+
+/// Country element.
+pub struct Country<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> Country<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 3).then_some(Self { data })
+    }
+
+    /// ISO 3166-1 alpha-2 code, or `XX` for non-country entities.
+    pub fn code(&self) -> [u8; 2] {
+        [self.data[0], self.data[1]]
+    }
+
+    pub fn environment(&self) -> CountryEnvironment {
+        self.data[2].into()
+    }
+
+    /// Triplets, ignoring the trailing pad byte.
+    pub fn triplets(&self) -> impl Iterator<Item = CountryTriplet> + 'a {
+        self.data[3..].chunks_exact(3).map(|triplet| {
+            if triplet[0] >= 201 {
+                CountryTriplet::OperatingClass {
+                    extension_id: triplet[0],
+                    operating_class: triplet[1],
+                    coverage_class: triplet[2],
+                }
+            } else {
+                CountryTriplet::SubBand {
+                    first_channel: triplet[0],
+                    channels: triplet[1],
+                    max_power_dbm: triplet[2] as i8,
+                }
+            }
+        })
+    }
+
+    /// Maximum transmit power of the first subband containing the 2.4 or 5 GHz
+    /// `channel`. Subbands after an operating class triplet are numbered in
+    /// that class and are not considered.
+    pub fn max_power_dbm(&self, channel: u8) -> Option<i8> {
+        let mut triplets = self
+            .triplets()
+            .take_while(|triplet| matches!(triplet, CountryTriplet::SubBand { .. }));
+        triplets.find_map(|triplet| match triplet {
+            CountryTriplet::SubBand {
+                first_channel,
+                channels,
+                max_power_dbm,
+            } => {
+                let spacing = if first_channel <= 14 { 1 } else { 4 };
+                let offset = channel.checked_sub(first_channel)?;
+                (offset % spacing == 0 && offset / spacing < channels).then_some(max_power_dbm)
+            }
+            CountryTriplet::OperatingClass { .. } => None,
+        })
+    }
+}
+
+/// TPC Report element.
+pub struct TPCReport<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> TPCReport<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 2).then_some(Self { data })
+    }
+
+    pub fn tx_power_dbm(&self) -> i8 {
+        self.data[0] as i8
+    }
+
+    pub fn link_margin_db(&self) -> i8 {
+        self.data[1] as i8
+    }
+}
+
+/// Maximum transmit power interpretation of the Transmit Power Envelope element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransmitPowerInterpretation {
+    LocalEIRP,
+    LocalEIRPPSD,
+    RegulatoryClientEIRP,
+    RegulatoryClientEIRPPSD,
+    Other(u8),
+}
+
+impl TransmitPowerInterpretation {
+    /// Power spectral density in dBm/MHz rather than EIRP in dBm.
+    pub fn is_psd(&self) -> bool {
+        matches!(
+            self,
+            TransmitPowerInterpretation::LocalEIRPPSD
+                | TransmitPowerInterpretation::RegulatoryClientEIRPPSD
+        )
+    }
+}
+
+impl From<u8> for TransmitPowerInterpretation {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TransmitPowerInterpretation::LocalEIRP,
+            1 => TransmitPowerInterpretation::LocalEIRPPSD,
+            2 => TransmitPowerInterpretation::RegulatoryClientEIRP,
+            3 => TransmitPowerInterpretation::RegulatoryClientEIRPPSD,
+            _ => TransmitPowerInterpretation::Other(value),
+        }
+    }
+}
+
+/// Transmit Power Envelope element.
+pub struct TransmitPowerEnvelope<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> TransmitPowerEnvelope<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        if data.is_empty() {
+            return None;
+        }
+        let envelope = Self { data };
+        (data.len() > envelope.power_count()).then_some(envelope)
+    }
+
+    pub fn interpretation(&self) -> TransmitPowerInterpretation {
+        ((self.data[0] >> 3) & 0x07).into()
+    }
+
+    /// Maximum transmit power category, 0 for default and 1 for subordinate devices.
+    pub fn category(&self) -> u8 {
+        self.data[0] >> 6
+    }
+
+    /// Number of power fields.
+    pub fn power_count(&self) -> usize {
+        let count = (self.data[0] & 0x07) as usize;
+        match (self.interpretation().is_psd(), count) {
+            (true, 0) => 1,
+            (true, count) => 1 << (count - 1),
+            (false, count) => count + 1,
+        }
+    }
+
+    /// Maximum transmit power in 0.5 dB steps: per 20, 40, 80 and 160 MHz
+    /// bandwidth for EIRP, per 20 MHz subchannel for PSD.
+    pub fn powers(&self) -> impl Iterator<Item = f32> + 'a {
+        self.data[1..=self.power_count()]
+            .iter()
+            .map(|&power| power as i8 as f32 / 2.0)
+    }
+}
+
+/// Channel switch announced in a CSA element, ECSA element or action frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelSwitch {
+    /// Transmissions must stop until the switch when 1.
+    pub mode: u8,
+    /// New operating class, only in extended announcements.
+    pub operating_class: Option<u8>,
+    pub channel: u8,
+    /// Beacon intervals until the switch.
+    pub count: u8,
+}
+
+impl ChannelSwitch {
+    /// Parses a CSA element including its header, as carried by action frames.
+    pub(super) fn from_element(buf: &[u8]) -> Result<Self, ParseError> {
+        let element = slice_at(buf, 0, 5)?;
+        if element[0] != InfoElementId::ChannelSwitch as u8 || element[1] < 3 {
+            return Err(ParseError::InvalidElement { id: element[0] });
+        }
+        Self::new(&element[2..])
+    }
+
+    /// Parses a CSA element body.
+    pub(super) fn new(data: &[u8]) -> Result<Self, ParseError> {
+        let fields = slice_at(data, 0, 3)?;
+        Ok(Self {
+            mode: fields[0],
+            operating_class: None,
+            channel: fields[1],
+            count: fields[2],
+        })
+    }
+
+    /// Parses an ECSA element body or extended announcement fields.
+    pub(super) fn new_extended(data: &[u8]) -> Result<Self, ParseError> {
+        let fields = slice_at(data, 0, 4)?;
+        Ok(Self {
+            mode: fields[0],
+            operating_class: Some(fields[1]),
+            channel: fields[2],
+            count: fields[3],
+        })
+    }
+}
+
+/// Channel Switch Announcement or Extended Channel Switch Announcement element.
+pub struct ChannelSwitchAnnouncement<'a> {
+    pub(super) data: &'a [u8],
+    pub switch: ChannelSwitch,
+}
+
+/// Wide Bandwidth Channel Switch element, the new VHT channel width.
+pub struct WideBandwidthChannelSwitch<'a> {
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> WideBandwidthChannelSwitch<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 3).then_some(Self { data })
+    }
+
+    /// New channel width, as the VHT Operation channel width field.
+    pub fn channel_width(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// New channel center frequency segment 0.
+    pub fn ccfs0(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// New channel center frequency segment 1.
+    pub fn ccfs1(&self) -> u8 {
+        self.data[2]
+    }
+
+    /// New channel, `None` for 20 or 40 MHz.
+    pub fn channel(&self) -> Option<Channel> {
+        vht_channel(self.channel_width(), self.ccfs0(), self.ccfs1())
+    }
+}
@@ -0,0 +1,317 @@
+use super::vendor::MICROSOFT_OUI;
+use crate::misc::ParseError;
+use core::fmt::{self, Display, Formatter};
+
+const IEEE80211_OUI: [u8; 3] = [0x00, 0x0f, 0xac];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherSuite {
+    UseGroup,
+    WEP40,
+    TKIP,
+    CCMP128,
+    WEP104,
+    BIPCMAC128,
+    GroupAddressedTrafficNotAllowed,
+    GCMP128,
+    GCMP256,
+    CCMP256,
+    BIPGMAC128,
+    BIPGMAC256,
+    BIPCMAC256,
+    Other([u8; 4]),
+}
+
+impl CipherSuite {
+    fn new(suite: [u8; 4], oui: [u8; 3]) -> Self {
+        if suite[..3] != oui {
+            return CipherSuite::Other(suite);
+        }
+        match suite[3] {
+            0 => CipherSuite::UseGroup,
+            1 => CipherSuite::WEP40,
+            2 => CipherSuite::TKIP,
+            4 => CipherSuite::CCMP128,
+            5 => CipherSuite::WEP104,
+            6 => CipherSuite::BIPCMAC128,
+            7 => CipherSuite::GroupAddressedTrafficNotAllowed,
+            8 => CipherSuite::GCMP128,
+            9 => CipherSuite::GCMP256,
+            10 => CipherSuite::CCMP256,
+            11 => CipherSuite::BIPGMAC128,
+            12 => CipherSuite::BIPGMAC256,
+            13 => CipherSuite::BIPCMAC256,
+            _ => CipherSuite::Other(suite),
+        }
+    }
+}
+
+impl Display for CipherSuite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CipherSuite::UseGroup => "group",
+            CipherSuite::WEP40 => "WEP-40",
+            CipherSuite::TKIP => "TKIP",
+            CipherSuite::CCMP128 => "CCMP-128",
+            CipherSuite::WEP104 => "WEP-104",
+            CipherSuite::BIPCMAC128 => "BIP-CMAC-128",
+            CipherSuite::GroupAddressedTrafficNotAllowed => "no group traffic",
+            CipherSuite::GCMP128 => "GCMP-128",
+            CipherSuite::GCMP256 => "GCMP-256",
+            CipherSuite::CCMP256 => "CCMP-256",
+            CipherSuite::BIPGMAC128 => "BIP-GMAC-128",
+            CipherSuite::BIPGMAC256 => "BIP-GMAC-256",
+            CipherSuite::BIPCMAC256 => "BIP-CMAC-256",
+            CipherSuite::Other(s) => {
+                return write!(f, "{:02x}-{:02x}-{:02x}:{}", s[0], s[1], s[2], s[3])
+            }
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Authentication and key management suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AKMSuite {
+    IEEE8021X,
+    PSK,
+    FTIEEE8021X,
+    FTPSK,
+    IEEE8021XSHA256,
+    PSKSHA256,
+    TDLS,
+    SAE,
+    FTSAE,
+    SuiteB,
+    SuiteB192,
+    FTIEEE8021XSHA384,
+    FILSSHA256,
+    FILSSHA384,
+    FTFILSSHA256,
+    FTFILSSHA384,
+    OWE,
+    FTPSKSHA384,
+    PSKSHA384,
+    SAEExtKey,
+    FTSAEExtKey,
+    Other([u8; 4]),
+}
+
+impl AKMSuite {
+    fn new(suite: [u8; 4], oui: [u8; 3]) -> Self {
+        if suite[..3] != oui {
+            return AKMSuite::Other(suite);
+        }
+        match (oui == MICROSOFT_OUI, suite[3]) {
+            (_, 1) => AKMSuite::IEEE8021X,
+            (_, 2) => AKMSuite::PSK,
+            (true, _) => AKMSuite::Other(suite),
+            (false, 3) => AKMSuite::FTIEEE8021X,
+            (false, 4) => AKMSuite::FTPSK,
+            (false, 5) => AKMSuite::IEEE8021XSHA256,
+            (false, 6) => AKMSuite::PSKSHA256,
+            (false, 7) => AKMSuite::TDLS,
+            (false, 8) => AKMSuite::SAE,
+            (false, 9) => AKMSuite::FTSAE,
+            (false, 11) => AKMSuite::SuiteB,
+            (false, 12) => AKMSuite::SuiteB192,
+            (false, 13) => AKMSuite::FTIEEE8021XSHA384,
+            (false, 14) => AKMSuite::FILSSHA256,
+            (false, 15) => AKMSuite::FILSSHA384,
+            (false, 16) => AKMSuite::FTFILSSHA256,
+            (false, 17) => AKMSuite::FTFILSSHA384,
+            (false, 18) => AKMSuite::OWE,
+            (false, 19) => AKMSuite::FTPSKSHA384,
+            (false, 20) => AKMSuite::PSKSHA384,
+            (false, 24) => AKMSuite::SAEExtKey,
+            (false, 25) => AKMSuite::FTSAEExtKey,
+            _ => AKMSuite::Other(suite),
+        }
+    }
+
+    pub fn is_psk(&self) -> bool {
+        matches!(
+            self,
+            AKMSuite::PSK
+                | AKMSuite::FTPSK
+                | AKMSuite::PSKSHA256
+                | AKMSuite::FTPSKSHA384
+                | AKMSuite::PSKSHA384
+        )
+    }
+
+    pub fn is_sae(&self) -> bool {
+        matches!(
+            self,
+            AKMSuite::SAE | AKMSuite::FTSAE | AKMSuite::SAEExtKey | AKMSuite::FTSAEExtKey
+        )
+    }
+
+    /// 802.1X and FILS authentication against an authentication server.
+    pub fn is_enterprise(&self) -> bool {
+        matches!(
+            self,
+            AKMSuite::IEEE8021X
+                | AKMSuite::FTIEEE8021X
+                | AKMSuite::IEEE8021XSHA256
+                | AKMSuite::SuiteB
+                | AKMSuite::SuiteB192
+                | AKMSuite::FTIEEE8021XSHA384
+                | AKMSuite::FILSSHA256
+                | AKMSuite::FILSSHA384
+                | AKMSuite::FTFILSSHA256
+                | AKMSuite::FTFILSSHA384
+        )
+    }
+
+    pub fn is_suite_b(&self) -> bool {
+        matches!(self, AKMSuite::SuiteB | AKMSuite::SuiteB192)
+    }
+}
+
+/// RSN capabilities field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RSNCapabilities(pub u16);
+
+impl RSNCapabilities {
+    pub fn has_preauth(&self) -> bool {
+        self.0 & 0x0001 != 0
+    }
+
+    /// Management frame protection required.
+    pub fn is_mfp_required(&self) -> bool {
+        self.0 & 0x0040 != 0
+    }
+
+    /// Management frame protection capable.
+    pub fn is_mfp_capable(&self) -> bool {
+        self.0 & 0x0080 != 0
+    }
+
+    pub fn has_extended_key_id(&self) -> bool {
+        self.0 & 0x2000 != 0
+    }
+
+    /// Operating channel validation capable.
+    pub fn is_ocv_capable(&self) -> bool {
+        self.0 & 0x4000 != 0
+    }
+}
+
+/// RSN element, or the body of the WPA vendor element after its OUI and type.
+///
+/// Every field after the version is optional, an element may end at any field
+/// boundary.
+#[derive(Debug, Clone)]
+pub struct RSN<'a> {
+    /// Element body, as encoded.
+    pub(super) data: &'a [u8],
+    oui: [u8; 3],
+    pub version: u16,
+    pub group_cipher: Option<CipherSuite>,
+    pairwise: &'a [u8],
+    akm: &'a [u8],
+    pub capabilities: Option<RSNCapabilities>,
+    pmkids: &'a [u8],
+    pub group_management_cipher: Option<CipherSuite>,
+}
+
+impl<'a> RSN<'a> {
+    /// Parses an RSN element body.
+    pub(super) fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse_fields(data, data, IEEE80211_OUI, 48)
+    }
+
+    /// Parses a WPA vendor element body, starting with its OUI and type.
+    pub(super) fn parse_wpa(data: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse_fields(data, &data[4..], MICROSOFT_OUI, 221)
+    }
+
+    fn parse_fields(
+        data: &'a [u8],
+        fields: &'a [u8],
+        oui: [u8; 3],
+        id: u8,
+    ) -> Result<Self, ParseError> {
+        let mut reader = Reader { buf: fields, id };
+        let version = reader.u16()?.ok_or(ParseError::InvalidElement { id })?;
+        let group_cipher = reader.suite()?.map(|suite| CipherSuite::new(suite, oui));
+        let pairwise = reader.suite_list()?;
+        let akm = reader.suite_list()?;
+        let capabilities = reader.u16()?.map(RSNCapabilities);
+        let pmkids = match reader.u16()? {
+            Some(count) => reader.take(count as usize * 16)?,
+            None => &[],
+        };
+        let group_management_cipher = reader.suite()?.map(|suite| CipherSuite::new(suite, oui));
+        Ok(Self {
+            data,
+            oui,
+            version,
+            group_cipher,
+            pairwise,
+            akm,
+            capabilities,
+            pmkids,
+            group_management_cipher,
+        })
+    }
+
+    pub fn pairwise_ciphers(&self) -> impl Iterator<Item = CipherSuite> + 'a {
+        let oui = self.oui;
+        suites(self.pairwise).map(move |suite| CipherSuite::new(suite, oui))
+    }
+
+    pub fn akm_suites(&self) -> impl Iterator<Item = AKMSuite> + 'a {
+        let oui = self.oui;
+        suites(self.akm).map(move |suite| AKMSuite::new(suite, oui))
+    }
+
+    pub fn pmkids(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.pmkids.chunks_exact(16)
+    }
+}
+
+fn suites(buf: &[u8]) -> impl Iterator<Item = [u8; 4]> + '_ {
+    buf.chunks_exact(4).map(|suite| suite.try_into().unwrap())
+}
+
+/// Reads optional trailing fields, an empty buffer ending the element.
+struct Reader<'a> {
+    buf: &'a [u8],
+    /// Element ID reported when a field is truncated.
+    id: u8,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        if self.buf.len() < len {
+            return Err(ParseError::InvalidElement { id: self.id });
+        }
+        let (field, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(field)
+    }
+
+    fn u16(&mut self) -> Result<Option<u16>, ParseError> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        let field = self.take(2)?;
+        Ok(Some(u16::from_le_bytes([field[0], field[1]])))
+    }
+
+    fn suite(&mut self) -> Result<Option<[u8; 4]>, ParseError> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn suite_list(&mut self) -> Result<&'a [u8], ParseError> {
+        match self.u16()? {
+            Some(count) => self.take(count as usize * 4),
+            None => Ok(&[]),
+        }
+    }
+}
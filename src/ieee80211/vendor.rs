@@ -0,0 +1,432 @@
+use super::element::{InfoElement, InfoElementId};
+use super::field::MACAddr;
+use super::rsn::RSN;
+use crate::misc::{MemCast, ParseError};
+use core::fmt::{self, Display, Formatter};
+use core::mem;
+
+pub const MICROSOFT_OUI: [u8; 3] = [0x00, 0x50, 0xf2];
+pub const WFA_OUI: [u8; 3] = [0x50, 0x6f, 0x9a];
+
+/// Vendor type of Microsoft and Wi-Fi Alliance elements, after the OUI.
+pub(super) const WPA_TYPE: u8 = 1;
+pub(super) const WMM_TYPE: u8 = 2;
+pub(super) const WPS_TYPE: u8 = 4;
+pub(super) const P2P_TYPE: u8 = 9;
+pub(super) const WIFI_AWARE_TYPE: u8 = 0x13;
+pub(super) const OWE_TRANSITION_TYPE: u8 = 0x1c;
+
+/// Name of the organization an OUI is registered to, for a few common ones.
+pub fn oui_name(oui: [u8; 3]) -> Option<&'static str> {
+    Some(match oui {
+        MICROSOFT_OUI => "Microsoft",
+        WFA_OUI => "Wi-Fi Alliance",
+        [0x00, 0x03, 0x7f] => "Atheros",
+        [0x00, 0x0b, 0x86] => "Aruba",
+        [0x00, 0x0c, 0x43] => "Ralink",
+        [0x00, 0x10, 0x18] => "Broadcom",
+        [0x00, 0x17, 0xf2] => "Apple",
+        [0x00, 0x40, 0x96] => "Cisco",
+        [0x00, 0x90, 0x4c] => "Epigram",
+        [0x00, 0xe0, 0x4c] => "Realtek",
+        [0x8c, 0xfd, 0xf0] => "Qualcomm",
+        _ => return None,
+    })
+}
+
+/// Vendor-specific element without a typed view.
+pub struct VendorElement<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> VendorElement<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        (data.len() >= 3).then_some(Self { data })
+    }
+
+    pub fn oui(&self) -> [u8; 3] {
+        self.data[..3].try_into().unwrap()
+    }
+
+    /// First byte after the OUI, a type for most vendors.
+    pub fn vendor_type(&self) -> Option<u8> {
+        self.data.get(3).copied()
+    }
+
+    /// Body after the OUI.
+    pub fn data(&self) -> &'a [u8] {
+        &self.data[3..]
+    }
+
+    /// Short description, e.g. `Broadcom (00:10:18) type 2`.
+    pub fn describe(&self) -> String {
+        let oui = self.oui();
+        let mut description = format!("{:02x}:{:02x}:{:02x}", oui[0], oui[1], oui[2]);
+        if let Some(name) = oui_name(oui) {
+            description = format!("{} ({})", name, description);
+        }
+        match self.vendor_type() {
+            Some(vendor_type) => format!("{} type {}", description, vendor_type),
+            None => description,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessCategory {
+    BestEffort,
+    Background,
+    Video,
+    Voice,
+}
+
+impl Display for AccessCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AccessCategory::BestEffort => "BE",
+            AccessCategory::Background => "BK",
+            AccessCategory::Video => "VI",
+            AccessCategory::Voice => "VO",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// EDCA parameters of one access category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ACParameters {
+    pub ac: AccessCategory,
+    pub aifsn: u8,
+    /// Admission control mandatory.
+    pub acm: bool,
+    pub cw_min: u16,
+    pub cw_max: u16,
+    /// TXOP limit in microseconds, 0 for a single frame.
+    pub txop_limit_us: u32,
+}
+
+impl ACParameters {
+    fn new(record: &[u8]) -> Self {
+        let ac = match (record[0] >> 5) & 0x03 {
+            0 => AccessCategory::BestEffort,
+            1 => AccessCategory::Background,
+            2 => AccessCategory::Video,
+            _ => AccessCategory::Voice,
+        };
+        Self {
+            ac,
+            aifsn: record[0] & 0x0f,
+            acm: record[0] & 0x10 != 0,
+            cw_min: (1 << (record[1] & 0x0f)) - 1,
+            cw_max: (1 << (record[1] >> 4)) - 1,
+            txop_limit_us: u16::from_le_bytes([record[2], record[3]]) as u32 * 32,
+        }
+    }
+}
+
+/// WMM information or parameter element.
+pub struct WMM<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> WMM<'a> {
+    const INFO_LEN: usize = 7;
+    const PARAMETER_LEN: usize = 24;
+
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        let wmm = Self { data };
+        let len = match data.get(4) {
+            Some(0) => Self::INFO_LEN,
+            Some(1) => Self::PARAMETER_LEN,
+            _ => return None,
+        };
+        (data.len() >= len).then_some(wmm)
+    }
+
+    pub fn is_parameter(&self) -> bool {
+        self.data[4] == 1
+    }
+
+    pub fn version(&self) -> u8 {
+        self.data[5]
+    }
+
+    /// QoS info, with the U-APSD flag of APs or the per-AC U-APSD flags of stations.
+    pub fn qos_info(&self) -> u8 {
+        self.data[6]
+    }
+
+    /// EDCA parameters of each access category, only in parameter elements.
+    pub fn ac_parameters(&self) -> impl Iterator<Item = ACParameters> + 'a {
+        let records = match self.is_parameter() {
+            true => &self.data[8..Self::PARAMETER_LEN],
+            false => &[],
+        };
+        records.chunks_exact(4).map(ACParameters::new)
+    }
+}
+
+/// WPS attribute types.
+pub mod wps {
+    pub const CONFIG_METHODS: u16 = 0x1008;
+    pub const DEVICE_NAME: u16 = 0x1011;
+    pub const MANUFACTURER: u16 = 0x1021;
+    pub const MODEL_NAME: u16 = 0x1023;
+    pub const MODEL_NUMBER: u16 = 0x1024;
+    pub const SERIAL_NUMBER: u16 = 0x1042;
+    pub const WPS_STATE: u16 = 0x1044;
+    pub const UUID_E: u16 = 0x1047;
+    pub const UUID_R: u16 = 0x1048;
+    pub const VERSION: u16 = 0x104a;
+    pub const PRIMARY_DEVICE_TYPE: u16 = 0x1054;
+    pub const AP_SETUP_LOCKED: u16 = 0x1057;
+}
+
+/// Wi-Fi Protected Setup element, made of big-endian type-length-value attributes.
+pub struct WPS<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> WPS<'a> {
+    /// Attribute types and values, stopping at the first truncated one.
+    pub fn attributes(&self) -> impl Iterator<Item = (u16, &'a [u8])> {
+        tlv_attributes(&self.data[4..])
+    }
+
+    pub fn attribute(&self, attribute_type: u16) -> Option<&'a [u8]> {
+        self.attributes()
+            .find(|&(ty, _)| ty == attribute_type)
+            .map(|(_, value)| value)
+    }
+
+    pub fn device_name(&self) -> Option<&'a [u8]> {
+        self.attribute(wps::DEVICE_NAME)
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a [u8]> {
+        self.attribute(wps::MANUFACTURER)
+    }
+
+    pub fn model_name(&self) -> Option<&'a [u8]> {
+        self.attribute(wps::MODEL_NAME)
+    }
+
+    pub fn model_number(&self) -> Option<&'a [u8]> {
+        self.attribute(wps::MODEL_NUMBER)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a [u8]> {
+        self.attribute(wps::SERIAL_NUMBER)
+    }
+
+    /// UUID-E of enrollees and APs, or UUID-R of registrars.
+    pub fn uuid(&self) -> Option<&'a [u8; 16]> {
+        let uuid = self
+            .attribute(wps::UUID_E)
+            .or_else(|| self.attribute(wps::UUID_R))?;
+        uuid.try_into().ok()
+    }
+
+    pub fn config_methods(&self) -> Option<u16> {
+        let methods = self.attribute(wps::CONFIG_METHODS)?;
+        Some(u16::from_be_bytes(methods.try_into().ok()?))
+    }
+
+    /// The AP refuses new registrars, after too many failed PIN attempts.
+    pub fn is_ap_setup_locked(&self) -> bool {
+        self.attribute(wps::AP_SETUP_LOCKED) == Some(&[1])
+    }
+
+    /// WPS is configured (2) or not (1).
+    pub fn state(&self) -> Option<u8> {
+        self.attribute(wps::WPS_STATE)?.first().copied()
+    }
+}
+
+fn tlv_attributes(buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut buf = buf;
+    core::iter::from_fn(move || match *buf {
+        [t0, t1, l0, l1, ref rest @ ..] => {
+            let len = u16::from_be_bytes([l0, l1]) as usize;
+            let value = rest.get(..len)?;
+            buf = &rest[len..];
+            Some((u16::from_be_bytes([t0, t1]), value))
+        }
+        _ => None,
+    })
+}
+
+/// P2P attribute IDs.
+pub mod p2p {
+    pub const CAPABILITY: u8 = 2;
+    pub const DEVICE_ID: u8 = 3;
+    pub const DEVICE_INFO: u8 = 13;
+}
+
+/// Wi-Fi Direct element, made of attributes with a little-endian length.
+pub struct P2P<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> P2P<'a> {
+    /// Attribute IDs and values, stopping at the first truncated one.
+    pub fn attributes(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        le_attributes(&self.data[4..])
+    }
+
+    pub fn attribute(&self, id: u8) -> Option<&'a [u8]> {
+        self.attributes()
+            .find(|&(attribute_id, _)| attribute_id == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Device and group capability bitmaps.
+    pub fn capability(&self) -> Option<(u8, u8)> {
+        match *self.attribute(p2p::CAPABILITY)? {
+            [device, group, ..] => Some((device, group)),
+            _ => None,
+        }
+    }
+
+    pub fn device_address(&self) -> Option<&'a MACAddr> {
+        let attribute = self
+            .attribute(p2p::DEVICE_INFO)
+            .or_else(|| self.attribute(p2p::DEVICE_ID))?;
+        attribute
+            .get(..mem::size_of::<MACAddr>())?
+            .try_cast_ref()
+            .ok()
+    }
+
+    /// Device name from the device info attribute.
+    pub fn device_name(&self) -> Option<&'a [u8]> {
+        let info = self.attribute(p2p::DEVICE_INFO)?;
+        // Address, config methods and primary device type, then secondary device types.
+        let secondary_count = *info.get(16)? as usize;
+        let (_, name) = tlv_attributes(info.get(17 + 8 * secondary_count..)?).next()?;
+        Some(name)
+    }
+}
+
+/// Wi-Fi Aware attribute IDs.
+pub mod wifi_aware {
+    pub const MASTER_INDICATION: u8 = 0x00;
+    pub const CLUSTER: u8 = 0x01;
+    pub const SERVICE_ID_LIST: u8 = 0x02;
+}
+
+/// Wi-Fi Aware (NAN) element of discovery beacons, with the P2P attribute layout.
+pub struct WiFiAware<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> WiFiAware<'a> {
+    /// Attribute IDs and values, stopping at the first truncated one.
+    pub fn attributes(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        le_attributes(&self.data[4..])
+    }
+
+    pub fn attribute(&self, id: u8) -> Option<&'a [u8]> {
+        self.attributes()
+            .find(|&(attribute_id, _)| attribute_id == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Master preference and random factor, which elect the anchor master.
+    pub fn master_indication(&self) -> Option<(u8, u8)> {
+        match *self.attribute(wifi_aware::MASTER_INDICATION)? {
+            [preference, random_factor, ..] => Some((preference, random_factor)),
+            _ => None,
+        }
+    }
+
+    /// Anchor master rank and hop count to the anchor master.
+    pub fn cluster(&self) -> Option<(u64, u8)> {
+        let cluster = self.attribute(wifi_aware::CLUSTER)?;
+        let rank = u64::from_le_bytes(cluster.get(..8)?.try_into().unwrap());
+        Some((rank, *cluster.get(8)?))
+    }
+
+    /// 6-byte hashes of the service names being published or subscribed to.
+    pub fn service_ids(&self) -> impl Iterator<Item = &'a [u8]> {
+        let list = self
+            .attribute(wifi_aware::SERVICE_ID_LIST)
+            .unwrap_or_default();
+        list.chunks_exact(6)
+    }
+}
+
+fn le_attributes(buf: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut buf = buf;
+    core::iter::from_fn(move || match *buf {
+        [id, l0, l1, ref rest @ ..] => {
+            let len = u16::from_le_bytes([l0, l1]) as usize;
+            let value = rest.get(..len)?;
+            buf = &rest[len..];
+            Some((id, value))
+        }
+        _ => None,
+    })
+}
+
+/// OWE transition mode element, pointing an open BSS to its OWE counterpart.
+pub struct OWETransition<'a> {
+    /// Element body, starting with the OUI.
+    pub(super) data: &'a [u8],
+}
+
+impl<'a> OWETransition<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Option<Self> {
+        let ssid_len = *data.get(10)? as usize;
+        (data.len() >= 11 + ssid_len).then_some(Self { data })
+    }
+
+    pub fn bssid(&self) -> &'a MACAddr {
+        self.data[4..10].cast_ref()
+    }
+
+    pub fn ssid(&self) -> &'a [u8] {
+        &self.data[11..11 + self.data[10] as usize]
+    }
+
+    /// Operating class and channel of the other BSS, when on another channel.
+    pub fn channel(&self) -> Option<(u8, u8)> {
+        match self.data[11 + self.data[10] as usize..] {
+            [operating_class, channel, ..] => Some((operating_class, channel)),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes a vendor-specific element by OUI and vendor type, keeping elements
+/// that fail to decode as [`InfoElement::VendorSpecific`].
+pub(super) fn parse(data: &[u8]) -> Result<InfoElement<'_>, ParseError> {
+    use InfoElement as IE;
+    let invalid = ParseError::InvalidElement {
+        id: InfoElementId::VendorSpecific as u8,
+    };
+    let vendor = VendorElement::new(data).ok_or(invalid)?;
+    Ok(match (vendor.oui(), vendor.vendor_type()) {
+        (MICROSOFT_OUI, Some(WPA_TYPE)) => match RSN::parse_wpa(data) {
+            Ok(wpa) => IE::WPA(wpa),
+            Err(_) => IE::VendorSpecific(vendor),
+        },
+        (MICROSOFT_OUI, Some(WMM_TYPE)) => match WMM::new(data) {
+            Some(wmm) => IE::WMM(wmm),
+            None => IE::VendorSpecific(vendor),
+        },
+        (MICROSOFT_OUI, Some(WPS_TYPE)) => IE::WPS(WPS { data }),
+        (WFA_OUI, Some(P2P_TYPE)) => IE::P2P(P2P { data }),
+        (WFA_OUI, Some(WIFI_AWARE_TYPE)) => IE::WiFiAware(WiFiAware { data }),
+        (WFA_OUI, Some(OWE_TRANSITION_TYPE)) => match OWETransition::new(data) {
+            Some(owe) => IE::OWETransition(owe),
+            None => IE::VendorSpecific(vendor),
+        },
+        _ => IE::VendorSpecific(vendor),
+    })
+}
@@ -0,0 +1,10 @@
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod bpf;
+pub mod ieee80211;
+pub mod misc;
+pub mod netlink;
+pub mod pcap;
+pub mod ppi;
+pub mod radiotap;
+pub mod socket;
@@ -1,28 +1,60 @@
-mod ieee80211;
-mod misc;
-mod netlink;
-mod radiotap;
-mod socket;
-
-use crate::ieee80211::field::MACAddr;
-use crate::misc::BytesDisplay;
-use crate::netlink::{InterfaceType80211, NL80211};
-use crate::radiotap::{AntennaSignal, Channel, Field, Iter as RTapIter};
+use bpf::FrameMatch;
 use ieee80211::element::InfoElement;
-use ieee80211::frame::{Beacon, Frame, Management, ProbeRequest};
+use ieee80211::field::{FrameControl, FrameType, MACAddr};
+use ieee80211::frame::{Beacon, Frame, FrameCheckSequence, Management, ProbeRequest};
+use ieee80211::phy::{BSSPhy, Band};
+use ieee80211::regulatory::CountryEnvironment;
+use ieee80211::rsn::{AKMSuite, RSN};
+use ieee80211::vendor::WPS;
+use misc::{BytesDisplay, ParseError, TimestampDisplay};
+use netlink::{InterfaceType80211, NL80211};
 use radiotap::RadioTap;
-use socket::PacketSocket;
+use radiotap::Summary as RTapSummary;
+use socket::{PacketSocket, PacketStatistics, RingConfig};
 use std::collections::HashSet;
 use std::env::args;
 use std::ffi::CString;
-use std::fmt::{Display, Formatter};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{io, mem, ptr};
+use wimon::{bpf, ieee80211, misc, netlink, pcap, ppi, radiotap, socket};
+
+const USAGE: &str = "Usage:   wimon [options] <interface name>
+         wimon [options] -r <capture file>
+Options:
+  -r <file>          read packets from a pcap or pcapng file
+  -w <file>          write received packets to a pcapng file
+  -C <MB>            start a new output file after this many megabytes
+  -G <seconds>       start a new output file after this many seconds
+  -W <count>         keep a ring of this many output files
+  -B <KiB>           size of a receive ring block (default 64)
+  -N <count>         number of receive ring blocks (default 16)
+  -s <seconds>       print capture statistics at this interval, 0 only on exit (default 60)
+  -d                 also show stations exchanging data frames with an AP
+  -j                 also show authentication, association and disconnection frames
+  -a                 also show action frames
+  -H                 prefer hardware receive timestamps when the driver provides them
+  -c                 verify the frame check sequence in software
+  -b drop|report     drop (default) or report frames with a bad FCS";
 
 fn main() {
-    if args().len() < 2 {
-        exit_failure("Usage:   wimon <interface name>");
+    let options = Options::parse().unwrap_or_else(|err| {
+        exit_failure(format!("{}\n{}", err, USAGE));
+    });
+    let source = options.source.clone();
+    let mut monitor = Monitor::new(options);
+    match source {
+        Source::Interface(if_name) => capture(&if_name, &mut monitor),
+        Source::File(path) => read_file(&path, &mut monitor),
     }
-    let if_name = args().nth(1).unwrap();
-    let if_name_cstr = CString::new(if_name).unwrap();
+}
+
+fn capture(if_name: &str, monitor: &mut Monitor) {
+    let if_name_cstr = CString::new(if_name).unwrap_or_else(|_| {
+        exit_failure("Invalid interface name");
+    });
     let if_idx = unsafe { libc::if_nametoindex(if_name_cstr.as_ptr()) };
     if if_idx == 0 {
         exit_failure("Interface not found");
@@ -39,126 +71,883 @@ fn main() {
     let pkt_sock = PacketSocket::open().unwrap_or_else(|err| {
         exit_failure(format!("socket: {}", err));
     });
+    // Only the frames shown are received, unless every frame is written to a file.
+    if monitor.options.write_file.is_none() {
+        let mut matches = vec![
+            FrameMatch::management(FrameControl::SUB_TYPE_BEACON),
+            FrameMatch::management(FrameControl::SUB_TYPE_PROBE_REQ),
+        ];
+        if monitor.options.data {
+            matches.push(FrameMatch {
+                frame_type: Some(FrameControl::TYPE_DATA),
+                ..FrameMatch::default()
+            });
+        }
+        if monitor.options.join {
+            matches.extend(
+                [
+                    FrameControl::SUB_TYPE_AUTH,
+                    FrameControl::SUB_TYPE_ASSOC_REQ,
+                    FrameControl::SUB_TYPE_ASSOC_RESP,
+                    FrameControl::SUB_TYPE_REASSOC_REQ,
+                    FrameControl::SUB_TYPE_REASSOC_RESP,
+                    FrameControl::SUB_TYPE_DEAUTH,
+                    FrameControl::SUB_TYPE_DISASSOC,
+                ]
+                .map(FrameMatch::management),
+            );
+        }
+        if monitor.options.action {
+            matches.extend(
+                [
+                    FrameControl::SUB_TYPE_ACTION,
+                    FrameControl::SUB_TYPE_ACTION_NO_ACK,
+                ]
+                .map(FrameMatch::management),
+            );
+        }
+        let program = bpf::compile(&matches);
+        if let Err(err) = pkt_sock.attach_filter(&program) {
+            eprintln!("Filter unavailable ({}), all frames are received", err);
+        }
+    }
+    if let Err(err) = pkt_sock.enable_timestamps(monitor.options.hw_timestamps) {
+        eprintln!(
+            "Receive timestamps unavailable ({}), using the current time",
+            err
+        );
+    }
+    // The ring must be set up before binding, otherwise frames are queued outside of it.
+    let ring = pkt_sock
+        .rx_ring(&monitor.options.ring)
+        .map_err(|err| eprintln!("Receive ring unavailable ({}), falling back to recv", err))
+        .ok();
     pkt_sock.bind(if_idx as u32).unwrap_or_else(|err| {
         exit_failure(format!("bind: {}", err));
     });
-    let mut beacons = HashSet::new();
-    let mut probes = HashSet::new();
     let mut buf = [0u8; 8 * 1024];
-    loop {
-        let recv_len = pkt_sock.recv(&mut buf[..]).unwrap_or_else(|err| {
-            exit_failure(format!("recv: {}", err));
+    let snap_len = match ring {
+        Some(_) => monitor.options.ring.block_size,
+        None => buf.len() as u32,
+    };
+    let mut writer = monitor.options.write_file.as_ref().map(|path| {
+        let description = iface.frequency.map(|frequency| {
+            match iface.channel_width.as_ref().and_then(|width| width.mhz()) {
+                Some(width) => format!("{} MHz, {} MHz wide", frequency, width),
+                None => format!("{} MHz", frequency),
+            }
         });
-        if recv_len <= 0 {
+        let interface = pcap::InterfaceDescription {
+            link_type: pcap::LINKTYPE_IEEE802_11_RADIOTAP,
+            snap_len,
+            name: if_name.to_owned(),
+            description,
+        };
+        let rotation = monitor.options.rotation.clone();
+        pcap::RotatingWriter::create(path, rotation, vec![interface]).unwrap_or_else(|err| {
+            exit_failure(format!("{}: {}", path, err));
+        })
+    });
+    let mut stats = CaptureStats::new(monitor.options.stats_interval);
+    handle_stop_signals();
+    if let Some(mut ring) = ring {
+        let mut losing = false;
+        while !STOP.load(Ordering::Relaxed) {
+            let frame = match ring.next(Some(POLL_INTERVAL)) {
+                Ok(Some(frame)) => frame,
+                Ok(None) => {
+                    stats.tick(&pkt_sock, monitor, writer.as_mut());
+                    continue;
+                }
+                Err(err) => exit_failure(format!("receive ring: {}", err)),
+            };
+            if frame.is_losing() && !losing {
+                eprintln!("Receive ring overflowed, frames were dropped (see -B and -N)");
+                losing = true;
+            }
+            let timestamp = frame.timestamp.time;
+            if let Some(writer) = &mut writer {
+                write_packet(writer, timestamp, frame.data);
+            }
+            monitor.handle(
+                pcap::LINKTYPE_IEEE802_11_RADIOTAP,
+                Some(timestamp),
+                frame.data,
+            );
+            stats.tick(&pkt_sock, monitor, writer.as_mut());
+        }
+    } else {
+        pkt_sock
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .unwrap_or_else(|err| {
+                exit_failure(format!("SO_RCVTIMEO: {}", err));
+            });
+    }
+    while !STOP.load(Ordering::Relaxed) {
+        let (recv_len, timestamp) = match pkt_sock.recv_timestamped(&mut buf[..]) {
+            Ok(received) => received,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                ) =>
+            {
+                stats.tick(&pkt_sock, monitor, writer.as_mut());
+                continue;
+            }
+            Err(err) => exit_failure(format!("recv: {}", err)),
+        };
+        if recv_len == 0 {
             continue;
         }
         let packet = &buf[..recv_len];
-        let rtap_len = RadioTap::len(packet);
-        let rtap_info = RTapInfo::from(RadioTap::iter(&packet[..rtap_len]));
-        let frame = &packet[rtap_len..];
-        if frame.control().is_beacon() {
-            handle_beacon(&rtap_info, frame, &mut beacons);
-        } else if frame.control().is_probe_request() {
-            handle_probe_request(&rtap_info, frame, &mut probes);
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp.time,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        };
+        if let Some(writer) = &mut writer {
+            write_packet(writer, timestamp, packet);
         }
+        monitor.handle(pcap::LINKTYPE_IEEE802_11_RADIOTAP, Some(timestamp), packet);
+        stats.tick(&pkt_sock, monitor, writer.as_mut());
     }
+    stats.report(&pkt_sock, monitor, writer.as_mut());
 }
 
-fn handle_beacon(rtap_info: &RTapInfo, frame: &[u8], beacons: &mut HashSet<MACAddr>) {
-    if beacons.contains(frame.bssid()) {
-        return;
+/// How often a capture wakes up without traffic to check for signals and statistics.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Set by SIGINT and SIGTERM to end the capture.
+static STOP: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn stop(_signal: libc::c_int) {
+    STOP.store(true, Ordering::Relaxed);
+}
+
+/// Installs handlers without SA_RESTART, so blocking reads return early.
+fn handle_stop_signals() {
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = stop as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            libc::sigaction(signal, &action, ptr::null_mut());
+        }
     }
-    match (frame.capability().has_ess(), frame.capability().has_ibss()) {
-        (true, false) => print!("AP STA"),
-        (false, true) => print!("Ad-hoc"),
-        (true, true) => print!("Mesh STA"),
-        (false, false) => print!("OCB STA"),
+}
+
+/// Kernel counters of a live capture, accumulated across reads.
+struct CaptureStats {
+    kernel: PacketStatistics,
+    started: Duration,
+    interval: Option<Duration>,
+    last_report: Instant,
+}
+
+impl CaptureStats {
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            kernel: PacketStatistics::default(),
+            started: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+            interval,
+            last_report: Instant::now(),
+        }
     }
-    print!(": {}, BSSID: {}", frame.ta(), frame.bssid());
-    let mut info_elements = Beacon::info_elements(frame);
-    while let Some(ie) = info_elements.next() {
-        match ie {
-            InfoElement::SSID(ssid) => print!(", SSID: {}", BytesDisplay::from(ssid)),
-            InfoElement::DSSS(channel) => print!(", channel: {}", channel),
-            _ => (),
+
+    /// Reports the statistics when the interval has elapsed.
+    fn tick(
+        &mut self,
+        pkt_sock: &PacketSocket,
+        monitor: &Monitor,
+        writer: Option<&mut pcap::RotatingWriter>,
+    ) {
+        if let Some(interval) = self.interval {
+            if self.last_report.elapsed() >= interval {
+                self.report(pkt_sock, monitor, writer);
+            }
         }
     }
-    println!(" ({})", rtap_info);
-    beacons.insert(frame.bssid().clone());
-}
 
-fn handle_probe_request(
-    rtap_info: &RTapInfo,
-    frame: &[u8],
-    probes: &mut HashSet<(MACAddr, Vec<u8>)>,
-) {
-    let mut ssid = vec![];
-    let mut info_elements = ProbeRequest::info_elements(frame);
-    while let Some(ie) = info_elements.next() {
-        match ie {
-            InfoElement::SSID(ie_ssid) => ssid = ie_ssid.to_owned(),
-            _ => (),
+    /// Prints the statistics and records them in the output file.
+    fn report(
+        &mut self,
+        pkt_sock: &PacketSocket,
+        monitor: &Monitor,
+        writer: Option<&mut pcap::RotatingWriter>,
+    ) {
+        match pkt_sock.statistics() {
+            Ok(stats) => self.kernel += stats,
+            Err(err) => eprintln!("PACKET_STATISTICS: {}", err),
+        }
+        self.last_report = Instant::now();
+        let kernel = &self.kernel;
+        let counters = format!(
+            "{} ring freezes, {} malformed, {} with bad FCS",
+            kernel.freeze_queue_count, monitor.bad_packets, monitor.bad_fcs
+        );
+        eprintln!(
+            "Statistics: {} received, {} dropped by the kernel, {} handled, {}",
+            kernel.packets, kernel.drops, monitor.packets, counters
+        );
+        if let Some(writer) = writer {
+            let stats = pcap::InterfaceStatistics {
+                start: Some(self.started),
+                end: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default(),
+                received: Some(kernel.packets),
+                os_drops: Some(kernel.drops),
+                delivered: Some(monitor.packets),
+                comment: Some(counters),
+            };
+            writer.write_statistics(0, &stats).unwrap_or_else(|err| {
+                exit_failure(format!("write: {}", err));
+            });
         }
     }
-    let probe = (frame.ta().clone(), ssid);
-    if probes.contains(&probe) {
-        return;
+}
+
+fn write_packet(writer: &mut pcap::RotatingWriter, timestamp: Duration, packet: &[u8]) {
+    writer
+        .write_packet(0, timestamp, packet)
+        .unwrap_or_else(|err| {
+            exit_failure(format!("write: {}", err));
+        });
+}
+
+fn read_file(path: &str, monitor: &mut Monitor) {
+    let mut reader = pcap::Reader::open(path).unwrap_or_else(|err| {
+        exit_failure(format!("{}: {}", path, err));
+    });
+    loop {
+        let record = reader.next_record().unwrap_or_else(|err| {
+            exit_failure(format!("{}: {}", path, err));
+        });
+        let Some(record) = record else {
+            break;
+        };
+        monitor.handle(record.link_type, Some(record.timestamp), record.data);
+    }
+}
+
+#[derive(Clone)]
+enum Source {
+    Interface(String),
+    File(String),
+}
+
+struct Options {
+    source: Source,
+    verify_fcs: bool,
+    bad_fcs: BadFcsAction,
+    write_file: Option<String>,
+    rotation: pcap::Rotation,
+    ring: RingConfig,
+    stats_interval: Option<Duration>,
+    hw_timestamps: bool,
+    data: bool,
+    join: bool,
+    action: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum BadFcsAction {
+    Drop,
+    Report,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut if_name = None;
+        let mut read_file = None;
+        let mut verify_fcs = false;
+        let mut hw_timestamps = false;
+        let mut data = false;
+        let mut join = false;
+        let mut action = false;
+        let mut bad_fcs = BadFcsAction::Drop;
+        let mut write_file = None;
+        let mut rotation = pcap::Rotation::default();
+        let mut ring = RingConfig::default();
+        let mut stats_interval = Some(Duration::from_secs(60));
+        let mut args = args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-c" => verify_fcs = true,
+                "-H" => hw_timestamps = true,
+                "-d" => data = true,
+                "-j" => join = true,
+                "-a" => action = true,
+                "-r" => read_file = Some(args.next().ok_or("-r expects a file name")?),
+                "-w" => write_file = Some(args.next().ok_or("-w expects a file name")?),
+                "-C" => {
+                    let megabytes: u64 = number(&arg, args.next())?;
+                    rotation.max_size = Some(
+                        megabytes
+                            .checked_mul(1_000_000)
+                            .ok_or("-C file size is too large")?,
+                    );
+                }
+                "-G" => rotation.max_age = Some(Duration::from_secs(number(&arg, args.next())?)),
+                "-W" => rotation.file_count = Some(number(&arg, args.next())?),
+                "-B" => {
+                    let kilobytes: u32 = number(&arg, args.next())?;
+                    ring.block_size = kilobytes
+                        .checked_mul(1024)
+                        .ok_or("-B block size is too large")?;
+                }
+                "-N" => ring.block_count = number(&arg, args.next())?,
+                "-s" => {
+                    let seconds = number(&arg, args.next())?;
+                    stats_interval = Some(Duration::from_secs(seconds)).filter(|_| seconds > 0);
+                }
+                "-b" => {
+                    bad_fcs = match args.next().as_deref() {
+                        Some("drop") => BadFcsAction::Drop,
+                        Some("report") => BadFcsAction::Report,
+                        _ => return Err("-b expects drop or report".to_owned()),
+                    }
+                }
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+                _ if if_name.is_none() => if_name = Some(arg),
+                _ => return Err(format!("Unexpected argument {}", arg)),
+            }
+        }
+        let source = match (if_name, read_file) {
+            (Some(_), Some(_)) => return Err("Interface name and -r are exclusive".to_owned()),
+            (Some(if_name), None) => Source::Interface(if_name),
+            (None, Some(path)) => Source::File(path),
+            (None, None) => return Err("Interface name is missing".to_owned()),
+        };
+        if write_file.is_some() && matches!(source, Source::File(_)) {
+            return Err("-w requires an interface".to_owned());
+        }
+        if write_file.is_none() && (rotation.is_enabled() || rotation.file_count.is_some()) {
+            return Err("-C, -G and -W require -w".to_owned());
+        }
+        if rotation.file_count.is_some() && !rotation.is_enabled() {
+            return Err("-W requires -C or -G".to_owned());
+        }
+        Ok(Self {
+            source,
+            verify_fcs,
+            bad_fcs,
+            write_file,
+            rotation,
+            ring,
+            stats_interval,
+            hw_timestamps,
+            data,
+            join,
+            action,
+        })
     }
-    println!(
-        "STA: {} probe SSID: {} ({})",
-        probe.0,
-        BytesDisplay::from(probe.1.as_slice()),
-        rtap_info,
-    );
-    probes.insert(probe);
 }
 
-fn exit_failure<T: Display>(failure: T) -> ! {
-    eprintln!("{}", failure);
-    std::process::exit(1);
+fn number<T: FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("{} expects a number", option))
 }
 
-#[derive(Debug)]
-struct RTapInfo {
-    channel: Option<Channel>,
-    signal: Option<AntennaSignal>,
+struct Monitor {
+    options: Options,
+    beacons: HashSet<MACAddr>,
+    probes: HashSet<(MACAddr, Vec<u8>)>,
+    /// Stations seen exchanging data with an AP, with its BSSID.
+    stations: HashSet<(MACAddr, MACAddr)>,
+    bad_packets: u64,
+    bad_fcs: u64,
+    /// Packets handed to the monitor, malformed ones included.
+    packets: u64,
+    /// Capture time of the packet being handled, when known.
+    timestamp: Option<Duration>,
 }
 
-impl Default for RTapInfo {
-    fn default() -> Self {
+impl Monitor {
+    fn new(options: Options) -> Self {
         Self {
-            channel: None,
-            signal: None,
+            options,
+            beacons: HashSet::new(),
+            probes: HashSet::new(),
+            stations: HashSet::new(),
+            bad_packets: 0,
+            bad_fcs: 0,
+            packets: 0,
+            timestamp: None,
         }
     }
-}
 
-impl From<RTapIter<'_>> for RTapInfo {
-    fn from(rtap_iter: RTapIter) -> Self {
-        let mut this = RTapInfo::default();
-        for field in rtap_iter {
-            match field {
-                Field::Channel(_) => this.channel = Some(Channel::try_from(field).unwrap()),
-                Field::AntennaSignal(_) => {
-                    this.signal = Some(AntennaSignal::try_from(field).unwrap())
+    fn handle(&mut self, link_type: u32, timestamp: Option<Duration>, packet: &[u8]) {
+        self.timestamp = timestamp;
+        self.packets += 1;
+        if let Err(err) = self.handle_packet(link_type, packet) {
+            self.bad_packets += 1;
+            eprintln!("Skipped malformed packet #{}: {}", self.bad_packets, err);
+        }
+    }
+
+    /// Timestamp prefix for output lines, empty when the capture time is unknown.
+    fn time(&self) -> String {
+        match self.timestamp {
+            Some(timestamp) => format!("{} ", TimestampDisplay::from(timestamp)),
+            None => String::new(),
+        }
+    }
+
+    fn handle_packet(&mut self, link_type: u32, packet: &[u8]) -> Result<(), ParseError> {
+        let (rtap_summary, mut frame) = match link_type {
+            pcap::LINKTYPE_IEEE802_11_RADIOTAP => {
+                let rtap_len = RadioTap::len(packet)?;
+                let rtap_iter = RadioTap::iter(&packet[..rtap_len])?;
+                (RTapSummary::try_from(rtap_iter)?, &packet[rtap_len..])
+            }
+            pcap::LINKTYPE_IEEE802_11 => (RTapSummary::default(), packet),
+            pcap::LINKTYPE_PPI => ppi::parse(packet)?,
+            _ => return Err(ParseError::UnsupportedLinkType(link_type)),
+        };
+        if let Some(flags) = &rtap_summary.flags {
+            let mut corrupted = flags.is_bad_fcs();
+            if flags.has_fcs() {
+                if self.options.verify_fcs && !corrupted {
+                    corrupted = !frame.has_valid_fcs()?;
+                }
+                frame = frame.split_fcs()?.0;
+            }
+            if corrupted {
+                self.bad_fcs += 1;
+                if self.options.bad_fcs == BadFcsAction::Report {
+                    let frame_type = match frame.control() {
+                        Ok(control) => format!("{}, ", control.frame_type()),
+                        Err(_) => String::new(),
+                    };
+                    println!(
+                        "{}Bad FCS #{}: {}{} bytes{}",
+                        self.time(),
+                        self.bad_fcs,
+                        frame_type,
+                        frame.len(),
+                        radio(&rtap_summary)
+                    );
+                }
+                return Ok(());
+            }
+        }
+        if frame.control()?.is_beacon() {
+            self.handle_beacon(&rtap_summary, frame)
+        } else if frame.control()?.is_probe_request() {
+            self.handle_probe_request(&rtap_summary, frame)
+        } else if frame.control()?.is_data() && self.options.data {
+            self.handle_data(&rtap_summary, frame)
+        } else if matches!(
+            frame.control()?.frame_type(),
+            FrameType::Action | FrameType::ActionNoAck
+        ) {
+            if self.options.action {
+                self.handle_action(&rtap_summary, frame)
+            } else {
+                Ok(())
+            }
+        } else if frame.control()?.is_mgmt() && self.options.join {
+            self.handle_join(&rtap_summary, frame)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn handle_beacon(
+        &mut self,
+        rtap_summary: &RTapSummary,
+        frame: &[u8],
+    ) -> Result<(), ParseError> {
+        if self.beacons.contains(frame.bssid()?) {
+            return Ok(());
+        }
+        let capability = frame.capability()?;
+        let mut line = match (capability.has_ess(), capability.has_ibss()) {
+            (true, false) => "AP STA",
+            (false, true) => "Ad-hoc",
+            (true, true) => "Mesh STA",
+            (false, false) => "OCB STA",
+        }
+        .to_owned();
+        line += &format!(": {}, BSSID: {}", frame.ta()?, frame.bssid()?);
+        let mut basic_rates = vec![];
+        let (mut rsn, mut wpa) = (None, None);
+        let mut wps_line = None;
+        let (mut country, mut power_constraint) = (None, 0);
+        let mut load = None;
+        // An element the typed decoders reject is skipped rather than hiding the whole BSS.
+        for ie in Beacon::info_elements(frame)?.flatten() {
+            match ie {
+                InfoElement::Country(element) => country = Some(element),
+                InfoElement::PowerConstraint(constraint) => power_constraint = constraint,
+                InfoElement::BSSLoad(element) => load = Some(element),
+                InfoElement::RSN(element) => rsn = Some(element),
+                InfoElement::WPA(element) => wpa = Some(element),
+                InfoElement::WPS(wps) => wps_line = wps_description(&wps),
+                InfoElement::SSID(ssid) => line += &format!(", SSID: {}", BytesDisplay::from(ssid)),
+                InfoElement::SupportedRates(rates) | InfoElement::ExtendedSupportedRates(rates) => {
+                    basic_rates.extend(rates.basic())
+                }
+                _ => (),
+            }
+        }
+        let phy = BSSPhy::from_elements(Beacon::info_elements(frame)?);
+        let phy_line = phy.to_string();
+        if !phy_line.is_empty() {
+            line += &format!(", {}", phy_line);
+        }
+        if let Some(country) = country {
+            let [a, b] = country.code().map(char::from);
+            line += &format!(", country: {}{}", a, b);
+            match country.environment() {
+                CountryEnvironment::Indoor | CountryEnvironment::Outdoor => {
+                    line += &format!(" ({})", country.environment())
                 }
                 _ => (),
             }
+            // Country subbands use 2.4 and 5 GHz channel numbers, which 6 GHz channels reuse.
+            let max_power = phy
+                .primary_channel
+                .filter(|_| phy.band != Some(Band::GHz6))
+                .and_then(|channel| country.max_power_dbm(channel));
+            if let Some(max_power) = max_power {
+                let max_power = max_power.saturating_sub_unsigned(power_constraint);
+                line += &format!(", max TX power: {} dBm", max_power);
+            }
+        }
+        if let Some(load) = load {
+            line += &format!(
+                ", stations: {}, utilization: {}%",
+                load.station_count(),
+                load.channel_utilization_percent()
+            );
+        }
+        line += &format!(
+            ", security: {}",
+            security_label(rsn.as_ref(), wpa.as_ref(), capability.has_privacy())
+        );
+        if let Some(wps_line) = wps_line {
+            line += &format!(", WPS: {}", wps_line);
+        }
+        if !basic_rates.is_empty() {
+            basic_rates.sort();
+            let rates: Vec<_> = basic_rates.iter().map(|rate| rate.to_string()).collect();
+            line += &format!(", basic rates: {} Mb/s", rates.join(" "));
+            if basic_rates.iter().any(|rate| rate.is_dsss()) {
+                line += " (11b)";
+            }
         }
-        this
+        println!("{}{}{}", self.time(), line, radio(rtap_summary));
+        self.beacons.insert(frame.bssid()?.clone());
+        Ok(())
     }
-}
 
-impl Display for RTapInfo {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut sep = "";
-        if let Some(channel) = &self.channel {
-            write!(f, "{}{} MHz", sep, channel.frequency_mhz())?;
-            sep = ", ";
+    fn handle_data(&mut self, rtap_summary: &RTapSummary, frame: &[u8]) -> Result<(), ParseError> {
+        use ieee80211::frame::Data;
+        let control = frame.control()?;
+        let (station, bssid, direction) = match (control.is_to_ds(), control.is_from_ds()) {
+            (true, false) => (Data::ta(frame)?, Data::ra(frame)?, "to"),
+            (false, true) => (Data::ra(frame)?, Data::ta(frame)?, "from"),
+            _ => return Ok(()),
+        };
+        if station.is_group() || control.frame_type().is_null() {
+            return Ok(());
+        }
+        let key = (station.clone(), bssid.clone());
+        if self.stations.contains(&key) {
+            return Ok(());
         }
-        if let Some(signal) = &self.signal {
-            write!(f, "{}{} dBm", sep, signal.dbm())?;
+        let mut line = format!("STA: {} data {} BSSID: {}", station, direction, bssid);
+        if let Some(qos) = frame.qos_control()? {
+            line += &format!(", TID: {}", qos.tid());
         }
+        if control.is_protected() {
+            line += ", protected";
+        } else if let Some((ethertype, _)) = frame.llc_snap()? {
+            line += &format!(", ethertype: {:#06x}", ethertype);
+        }
+        println!("{}{}{}", self.time(), line, radio(rtap_summary));
+        self.stations.insert(key);
         Ok(())
     }
+
+    /// Prints every step of a station joining or leaving a BSS, without deduplication.
+    fn handle_join(&mut self, rtap_summary: &RTapSummary, frame: &[u8]) -> Result<(), ParseError> {
+        use ieee80211::frame::{
+            AssociationRequest, AssociationResponse, Authentication, Deauthentication,
+            Disassociation, ReassociationRequest,
+        };
+        let (sa, da) = (frame.ta()?, frame.ra()?);
+        let line = match frame.control()?.frame_type() {
+            FrameType::Auth => {
+                let mut line = format!(
+                    "{} -> {} auth {}, seq: {}",
+                    sa,
+                    da,
+                    frame.algorithm()?,
+                    frame.sequence()?
+                );
+                if let Some(group) = frame.sae_group()? {
+                    line += &format!(", commit group: {}", group);
+                }
+                if let Some(send_confirm) = frame.sae_send_confirm()? {
+                    line += &format!(", send-confirm: {}", send_confirm);
+                }
+                line + &format!(", status: {}", Authentication::status(frame)?)
+            }
+            FrameType::AssocRequest | FrameType::ReassocRequest => {
+                let reassoc = frame.control()?.frame_type() == FrameType::ReassocRequest;
+                let (kind, elements) = if reassoc {
+                    ("reassoc", ReassociationRequest::info_elements(frame)?)
+                } else {
+                    ("assoc", AssociationRequest::info_elements(frame)?)
+                };
+                let mut line = format!("STA: {} {} request to BSSID: {}", sa, kind, da);
+                for ie in elements.flatten() {
+                    if let InfoElement::SSID(ssid) = ie {
+                        line += &format!(", SSID: {}", BytesDisplay::from(ssid));
+                    }
+                }
+                if reassoc {
+                    line += &format!(", current AP: {}", frame.current_ap()?);
+                }
+                line + &format!(
+                    ", listen interval: {}",
+                    AssociationRequest::listen_interval(frame)?
+                )
+            }
+            FrameType::AssocResponse | FrameType::ReassocResponse => {
+                let kind = match frame.control()?.frame_type() {
+                    FrameType::ReassocResponse => "reassoc",
+                    _ => "assoc",
+                };
+                let status = AssociationResponse::status(frame)?;
+                let mut line = format!(
+                    "STA: {} {} response from BSSID: {}, status: {}",
+                    da, kind, sa, status
+                );
+                if status.is_success() {
+                    line += &format!(", AID: {}", frame.aid()?);
+                }
+                line
+            }
+            FrameType::Deauth => format!(
+                "{} -> {} deauth, reason: {}",
+                sa,
+                da,
+                Deauthentication::reason(frame)?
+            ),
+            FrameType::Disassoc => format!(
+                "{} -> {} disassoc, reason: {}",
+                sa,
+                da,
+                Disassociation::reason(frame)?
+            ),
+            _ => return Ok(()),
+        };
+        println!("{}{}{}", self.time(), line, radio(rtap_summary));
+        Ok(())
+    }
+
+    fn handle_action(
+        &mut self,
+        rtap_summary: &RTapSummary,
+        frame: &[u8],
+    ) -> Result<(), ParseError> {
+        use ieee80211::action::ActionDetails;
+        use ieee80211::frame::Action;
+        let mut line = format!("{} -> {} action", frame.ta()?, frame.ra()?);
+        let Some(action) = frame.action()? else {
+            println!("{}{}, protected{}", self.time(), line, radio(rtap_summary));
+            return Ok(());
+        };
+        line += &format!(" {}", action.category);
+        match (action.name(), action.code) {
+            (Some(name), _) => line += &format!(" {}", name),
+            (None, Some(code)) => line += &format!(" {}", code),
+            (None, None) => (),
+        }
+        match action.details {
+            ActionDetails::ChannelSwitch(csa) => {
+                line += &format!(", channel: {}, count: {}", csa.channel, csa.count);
+                if let Some(operating_class) = csa.operating_class {
+                    line += &format!(", operating class: {}", operating_class);
+                }
+            }
+            ActionDetails::AddBARequest(req) => {
+                line += &format!(
+                    ", TID: {}, buffer size: {}, SSN: {}",
+                    req.parameters.tid(),
+                    req.parameters.buffer_size(),
+                    req.starting_sequence
+                );
+            }
+            ActionDetails::AddBAResponse(resp) => {
+                line += &format!(
+                    ", TID: {}, buffer size: {}, status: {}",
+                    resp.parameters.tid(),
+                    resp.parameters.buffer_size(),
+                    resp.status
+                );
+            }
+            ActionDetails::DelBA(delba) => {
+                line += &format!(", TID: {}, reason: {}", delba.tid(), delba.reason);
+            }
+            ActionDetails::SAQueryRequest { transaction_id }
+            | ActionDetails::SAQueryResponse { transaction_id } => {
+                line += &format!(", transaction: {}", transaction_id);
+            }
+            ActionDetails::NeighborReportResponse { reports, .. } => {
+                line += &neighbors(reports);
+            }
+            ActionDetails::BTMQuery {
+                reason, candidates, ..
+            } => {
+                line += &format!(", reason: {}", reason);
+                line += &neighbors(candidates);
+            }
+            ActionDetails::BTMRequest(req) => {
+                if req.is_disassociation_imminent() {
+                    line += &format!(
+                        ", disassociation imminent in {} TBTTs",
+                        req.disassociation_timer
+                    );
+                }
+                if req.bss_termination.is_some() {
+                    line += ", BSS termination";
+                }
+                line += &neighbors(req.candidates());
+            }
+            ActionDetails::BTMResponse(resp) => {
+                line += &format!(", status: {}", resp.status);
+                if let Some(target) = resp.target_bssid {
+                    line += &format!(", target: {}", target);
+                }
+            }
+            ActionDetails::VendorSpecific { oui, .. } => {
+                line += &format!(", OUI: {:02x}:{:02x}:{:02x}", oui[0], oui[1], oui[2]);
+            }
+            ActionDetails::NeighborReportRequest { .. } | ActionDetails::Other(_) => (),
+        }
+        println!("{}{}{}", self.time(), line, radio(rtap_summary));
+        Ok(())
+    }
+
+    fn handle_probe_request(
+        &mut self,
+        rtap_summary: &RTapSummary,
+        frame: &[u8],
+    ) -> Result<(), ParseError> {
+        let mut ssid = vec![];
+        for ie in ProbeRequest::info_elements(frame)?.flatten() {
+            if let InfoElement::SSID(ie_ssid) = ie {
+                ssid = ie_ssid.to_owned();
+            }
+        }
+        let probe = (frame.ta()?.clone(), ssid);
+        if self.probes.contains(&probe) {
+            return Ok(());
+        }
+        println!(
+            "{}STA: {} probe SSID: {}{}",
+            self.time(),
+            probe.0,
+            BytesDisplay::from(probe.1.as_slice()),
+            radio(rtap_summary),
+        );
+        self.probes.insert(probe);
+        Ok(())
+    }
+}
+
+/// WPS identity of a device, as `"name" (manufacturer model), setup locked`.
+fn wps_description(wps: &WPS) -> Option<String> {
+    let mut line = BytesDisplay::from(wps.device_name()?).to_string();
+    let model: Vec<_> = [wps.manufacturer(), wps.model_name(), wps.model_number()]
+        .into_iter()
+        .flatten()
+        .filter(|field| !field.is_empty())
+        .map(|field| String::from_utf8_lossy(field).trim().to_owned())
+        .collect();
+    if !model.is_empty() {
+        line += &format!(" ({})", model.join(" "));
+    }
+    if wps.is_ap_setup_locked() {
+        line += ", setup locked";
+    }
+    Some(line)
+}
+
+/// Security of a BSS from its RSN and WPA elements and the capability privacy bit.
+fn security_label(rsn: Option<&RSN>, wpa: Option<&RSN>, privacy: bool) -> &'static str {
+    let akms = |element: &RSN| element.akm_suites().collect::<Vec<_>>();
+    if let Some(rsn) = rsn {
+        let akms = akms(rsn);
+        let (sae, psk) = (
+            akms.iter().any(|akm| akm.is_sae()),
+            akms.iter().any(|akm| akm.is_psk()),
+        );
+        return if akms.iter().any(|akm| akm.is_suite_b()) {
+            "Suite-B"
+        } else if akms.iter().any(|akm| akm.is_enterprise()) {
+            "Enterprise"
+        } else if sae && psk {
+            "WPA2/WPA3 transition"
+        } else if sae {
+            "WPA3-SAE"
+        } else if psk && wpa.is_some() {
+            "WPA/WPA2-PSK"
+        } else if psk {
+            "WPA2-PSK"
+        } else if akms.contains(&AKMSuite::OWE) {
+            "OWE"
+        } else {
+            "RSN"
+        };
+    }
+    match wpa.map(akms) {
+        Some(akms) if akms.iter().any(|akm| akm.is_enterprise()) => "WPA-Enterprise",
+        Some(akms) if akms.iter().any(|akm| akm.is_psk()) => "WPA-PSK",
+        Some(_) => "WPA",
+        None if privacy => "WEP",
+        None => "Open",
+    }
+}
+
+/// Radio information suffix for output lines, empty when nothing is known.
+fn radio(rtap_summary: &RTapSummary) -> String {
+    let summary = rtap_summary.to_string();
+    if summary.is_empty() {
+        summary
+    } else {
+        format!(" ({})", summary)
+    }
+}
+
+/// Neighbor reports of a candidate list, as `, candidates: BSSID (channel N, preference N), ...`.
+fn neighbors(elements: ieee80211::element::InfoElementIter) -> String {
+    let mut reports = vec![];
+    for ie in elements.flatten() {
+        if let InfoElement::NeighborReport(report) = ie {
+            let mut report_line = format!("{} (channel {}", report.bssid(), report.channel());
+            if let Some(preference) = report.preference() {
+                report_line += &format!(", preference {}", preference);
+            }
+            reports.push(report_line + ")");
+        }
+    }
+    if reports.is_empty() {
+        return String::new();
+    }
+    format!(", candidates: {}", reports.join(", "))
+}
+
+fn exit_failure<T: Display>(failure: T) -> ! {
+    eprintln!("{}", failure);
+    std::process::exit(1);
 }
@@ -1,9 +1,11 @@
 use core::fmt::{Display, Formatter, Result};
+use core::time::Duration;
 use core::{mem, str};
 
 pub trait MemCast {
     fn cast_ref<T>(&self) -> &T;
     fn cast_mut<T>(&mut self) -> &mut T;
+    fn try_cast_ref<T>(&self) -> core::result::Result<&T, ParseError>;
 }
 
 impl MemCast for [u8] {
@@ -16,6 +18,99 @@ impl MemCast for [u8] {
         assert!(self.len() >= mem::size_of::<T>());
         unsafe { &mut *(self.as_ptr().cast::<T>() as *mut T) }
     }
+
+    fn try_cast_ref<T>(&self) -> core::result::Result<&T, ParseError> {
+        if self.len() < mem::size_of::<T>() {
+            return Err(ParseError::Truncated {
+                need: mem::size_of::<T>(),
+                have: self.len(),
+            });
+        }
+        if self.as_ptr().align_offset(mem::align_of::<T>()) != 0 {
+            return Err(ParseError::MisalignedField);
+        }
+        Ok(unsafe { &*self.as_ptr().cast::<T>() })
+    }
+}
+
+/// Error returned by the radiotap and 802.11 parsers on malformed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// Buffer is shorter than the header or field being read.
+    Truncated { need: usize, have: usize },
+    /// Radiotap header length is smaller than the fixed header or exceeds the packet.
+    BadRadioTapLength(usize),
+    /// PPI header length is smaller than the fixed header or exceeds the packet.
+    BadPPILength(usize),
+    /// Field does not fit at its required alignment.
+    MisalignedField,
+    /// Information element length runs past the end of the frame.
+    ElementOverrun { id: u8, len: usize },
+    /// Information element body is too short or inconsistent for its ID.
+    InvalidElement { id: u8 },
+    /// Packet is not an 802.11 frame with a known link-layer header.
+    UnsupportedLinkType(u32),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ParseError::Truncated { need, have } => {
+                write!(
+                    f,
+                    "truncated header ({} bytes needed, {} available)",
+                    need, have
+                )
+            }
+            ParseError::BadRadioTapLength(len) => write!(f, "bad radiotap length ({})", len),
+            ParseError::BadPPILength(len) => write!(f, "bad PPI length ({})", len),
+            ParseError::MisalignedField => write!(f, "misaligned field"),
+            ParseError::ElementOverrun { id, len } => {
+                write!(f, "element {} overruns frame (length {})", id, len)
+            }
+            ParseError::InvalidElement { id } => write!(f, "invalid element {}", id),
+            ParseError::UnsupportedLinkType(ty) => write!(f, "unsupported link type {}", ty),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns `len` bytes of `buf` starting at `offset`, or a truncation error.
+pub fn slice_at(buf: &[u8], offset: usize, len: usize) -> core::result::Result<&[u8], ParseError> {
+    buf.get(offset..offset + len).ok_or(ParseError::Truncated {
+        need: offset + len,
+        have: buf.len(),
+    })
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// IEEE 802.3 CRC-32, as used for the 802.11 frame check sequence.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
 }
 
 pub struct BytesDisplay<'a>(&'a [u8]);
@@ -35,3 +130,18 @@ impl<'a> From<&'a [u8]> for BytesDisplay<'a> {
         BytesDisplay(v)
     }
 }
+
+/// Displays a time since the Unix epoch as seconds with microsecond precision.
+pub struct TimestampDisplay(Duration);
+
+impl Display for TimestampDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}.{:06}", self.0.as_secs(), self.0.subsec_micros())
+    }
+}
+
+impl From<Duration> for TimestampDisplay {
+    fn from(v: Duration) -> Self {
+        TimestampDisplay(v)
+    }
+}
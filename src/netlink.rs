@@ -1,7 +1,7 @@
 use crate::misc::MemCast;
 use crate::socket::Socket;
 use std::ffi::{CStr, CString};
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::{io, mem, str};
 
 pub struct GenericNetlink(Socket);
@@ -22,10 +22,7 @@ impl GenericNetlink {
 
     pub fn check_error(&self, reply: &[u8]) -> io::Result<()> {
         if let Some(err_code) = reply.error_code() {
-            Err(Error::new(
-                ErrorKind::Other,
-                format!("netlink error ({})", err_code),
-            ))
+            Err(Error::other(format!("netlink error ({})", err_code)))
         } else {
             Ok(())
         }
@@ -88,10 +85,11 @@ trait GeMsg {
     fn header_mut(&mut self) -> &mut MsgHdr;
     fn header(&self) -> &MsgHdr;
     fn generic_header_mut(&mut self) -> &mut GeMsgHdr;
+    #[allow(dead_code)]
     fn generic_header(&self) -> &GeMsgHdr;
     fn create(&mut self, ty: u16, flags: u16, seq: u32, pid: u32, cmd: u8, version: u8);
     fn put_attr(&mut self, ty: u16, data: &[u8]);
-    fn attrs(&self) -> AttrIter;
+    fn attrs(&self) -> AttrIter<'_>;
     fn error_code(&self) -> Option<i32>;
 }
 
@@ -138,7 +136,7 @@ impl GeMsg for [u8] {
         self.header_mut().len += pad_len as u32;
     }
 
-    fn attrs(&self) -> AttrIter {
+    fn attrs(&self) -> AttrIter<'_> {
         let msg_len = self.header().len as usize;
         let hdr_len = mem::size_of::<MsgHdr>() + mem::size_of::<GeMsgHdr>();
         AttrIter(&self[hdr_len..msg_len])
@@ -247,12 +245,18 @@ pub struct Interface80211 {
     pub index: u32,
     pub name: String,
     pub r#type: InterfaceType80211,
+    pub frequency: Option<u32>,
+    pub channel_width: Option<ChannelWidth80211>,
+    pub center_freq1: Option<u32>,
 }
 
 impl Interface80211 {
     const ATTR_IFINDEX: u16 = 3;
     const ATTR_IFNAME: u16 = 4;
     const ATTR_IFTYPE: u16 = 5;
+    const ATTR_WIPHY_FREQ: u16 = 38;
+    const ATTR_CHANNEL_WIDTH: u16 = 159;
+    const ATTR_CENTER_FREQ1: u16 = 160;
 }
 
 impl From<AttrIter<'_>> for Interface80211 {
@@ -261,6 +265,9 @@ impl From<AttrIter<'_>> for Interface80211 {
             index: 0,
             name: String::new(),
             r#type: InterfaceType80211::Unspecified,
+            frequency: None,
+            channel_width: None,
+            center_freq1: None,
         };
         for (ty, data) in iter {
             match ty {
@@ -273,6 +280,13 @@ impl From<AttrIter<'_>> for Interface80211 {
                         .to_owned();
                 }
                 Interface80211::ATTR_IFTYPE => iface.r#type = InterfaceType80211::from(data),
+                Interface80211::ATTR_WIPHY_FREQ => iface.frequency = Some(*data.cast_ref::<u32>()),
+                Interface80211::ATTR_CHANNEL_WIDTH => {
+                    iface.channel_width = Some(ChannelWidth80211::from(data))
+                }
+                Interface80211::ATTR_CENTER_FREQ1 => {
+                    iface.center_freq1 = Some(*data.cast_ref::<u32>())
+                }
                 _ => (),
             }
         }
@@ -319,3 +333,66 @@ impl From<&[u8]> for InterfaceType80211 {
         }
     }
 }
+
+#[derive(Debug, PartialEq)]
+pub enum ChannelWidth80211 {
+    NoHT20,
+    W20,
+    W40,
+    W80,
+    W80P80,
+    W160,
+    W5,
+    W10,
+    W1,
+    W2,
+    W4,
+    W8,
+    W16,
+    W320,
+    Unknown,
+}
+
+impl ChannelWidth80211 {
+    pub fn mhz(&self) -> Option<u32> {
+        use ChannelWidth80211::*;
+        match self {
+            NoHT20 | W20 => Some(20),
+            W40 => Some(40),
+            W80 | W80P80 => Some(80),
+            W160 => Some(160),
+            W5 => Some(5),
+            W10 => Some(10),
+            W1 => Some(1),
+            W2 => Some(2),
+            W4 => Some(4),
+            W8 => Some(8),
+            W16 => Some(16),
+            W320 => Some(320),
+            Unknown => None,
+        }
+    }
+}
+
+impl From<&[u8]> for ChannelWidth80211 {
+    fn from(x: &[u8]) -> Self {
+        use ChannelWidth80211::*;
+        match *x.cast_ref::<u32>() {
+            0 => NoHT20,
+            1 => W20,
+            2 => W40,
+            3 => W80,
+            4 => W80P80,
+            5 => W160,
+            6 => W5,
+            7 => W10,
+            8 => W1,
+            9 => W2,
+            10 => W4,
+            11 => W8,
+            12 => W16,
+            13 => W320,
+            _ => Unknown,
+        }
+    }
+}
@@ -0,0 +1,790 @@
+use std::fs::File;
+use std::io::{self, BufReader, Error, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const LINKTYPE_IEEE802_11: u32 = 105;
+pub const LINKTYPE_IEEE802_11_RADIOTAP: u32 = 127;
+pub const LINKTYPE_PPI: u32 = 192;
+
+const PCAP_MAGIC_USEC: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NSEC: u32 = 0xa1b2_3c4d;
+const PCAPNG_SHB: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+const PCAPNG_IDB: u32 = 1;
+const PCAPNG_PB: u32 = 2;
+const PCAPNG_SPB: u32 = 3;
+const PCAPNG_ISB: u32 = 5;
+const PCAPNG_EPB: u32 = 6;
+const PCAPNG_OPT_END: u16 = 0;
+const PCAPNG_OPT_COMMENT: u16 = 1;
+const PCAPNG_OPT_SHB_USERAPPL: u16 = 4;
+const PCAPNG_OPT_IF_NAME: u16 = 2;
+const PCAPNG_OPT_IF_DESCRIPTION: u16 = 3;
+const PCAPNG_OPT_IF_TSRESOL: u16 = 9;
+const PCAPNG_OPT_ISB_STARTTIME: u16 = 2;
+const PCAPNG_OPT_ISB_ENDTIME: u16 = 3;
+const PCAPNG_OPT_ISB_IFRECV: u16 = 4;
+const PCAPNG_OPT_ISB_OSDROP: u16 = 7;
+const PCAPNG_OPT_ISB_USRDELIV: u16 = 8;
+
+/// Largest block or record accepted, to bound memory use on corrupted files.
+const MAX_RECORD_LEN: usize = 16 * 1024 * 1024;
+
+/// A packet read from a capture file.
+#[derive(Debug)]
+pub struct Record<'a> {
+    pub link_type: u32,
+    /// Capture time since the Unix epoch.
+    pub timestamp: Duration,
+    pub data: &'a [u8],
+}
+
+/// Reader for classic pcap and pcapng capture files.
+pub struct Reader<R: Read> {
+    input: R,
+    format: Format,
+    buf: Vec<u8>,
+}
+
+enum Format {
+    Pcap {
+        big_endian: bool,
+        nanos: bool,
+        link_type: u32,
+    },
+    PcapNg {
+        big_endian: bool,
+        interfaces: Vec<Interface>,
+    },
+}
+
+struct Interface {
+    link_type: u32,
+    /// Timestamp units per second.
+    ts_units: u64,
+}
+
+impl Reader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        let format = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (PCAPNG_SHB, _) => Format::PcapNg {
+                big_endian: false,
+                interfaces: vec![],
+            },
+            (PCAP_MAGIC_USEC | PCAP_MAGIC_NSEC, _) | (_, PCAP_MAGIC_USEC | PCAP_MAGIC_NSEC) => {
+                let big_endian =
+                    matches!(u32::from_be_bytes(magic), PCAP_MAGIC_USEC | PCAP_MAGIC_NSEC);
+                let nanos = read_u32(&magic, 0, big_endian) == PCAP_MAGIC_NSEC;
+                let mut header = [0u8; 20];
+                input.read_exact(&mut header)?;
+                Format::Pcap {
+                    big_endian,
+                    nanos,
+                    link_type: read_u32(&header, 16, big_endian) & 0x0fff_ffff,
+                }
+            }
+            _ => return Err(invalid_data("not a pcap or pcapng file")),
+        };
+        let mut reader = Self {
+            input,
+            format,
+            buf: vec![],
+        };
+        if let Format::PcapNg { .. } = reader.format {
+            reader.read_section_header()?;
+        }
+        Ok(reader)
+    }
+
+    /// Link type of a classic pcap file, or of the first pcapng interface seen so far.
+    pub fn link_type(&self) -> Option<u32> {
+        match &self.format {
+            Format::Pcap { link_type, .. } => Some(*link_type),
+            Format::PcapNg { interfaces, .. } => interfaces.first().map(|iface| iface.link_type),
+        }
+    }
+
+    /// Reads the next packet, returning `None` at the end of the file.
+    pub fn next_record(&mut self) -> io::Result<Option<Record<'_>>> {
+        match self.format {
+            Format::Pcap { .. } => self.next_pcap_record(),
+            Format::PcapNg { .. } => self.next_pcapng_record(),
+        }
+    }
+
+    fn next_pcap_record(&mut self) -> io::Result<Option<Record<'_>>> {
+        let Format::Pcap {
+            big_endian,
+            nanos,
+            link_type,
+        } = self.format
+        else {
+            unreachable!()
+        };
+        let mut header = [0u8; 16];
+        if !read_exact_or_eof(&mut self.input, &mut header)? {
+            return Ok(None);
+        }
+        let secs = read_u32(&header, 0, big_endian) as u64;
+        let frac = read_u32(&header, 4, big_endian);
+        let len = read_u32(&header, 8, big_endian) as usize;
+        self.read_buf(len)?;
+        let timestamp = if nanos {
+            Duration::new(secs, frac)
+        } else {
+            Duration::new(secs, 0) + Duration::from_micros(frac as u64)
+        };
+        Ok(Some(Record {
+            link_type,
+            timestamp,
+            data: &self.buf,
+        }))
+    }
+
+    fn next_pcapng_record(&mut self) -> io::Result<Option<Record<'_>>> {
+        let (link_type, timestamp, data) = loop {
+            let mut header = [0u8; 8];
+            if !read_exact_or_eof(&mut self.input, &mut header)? {
+                return Ok(None);
+            }
+            let big_endian = self.big_endian();
+            let ty = read_u32(&header, 0, big_endian);
+            if ty == PCAPNG_SHB {
+                self.read_section_header_body(header)?;
+                continue;
+            }
+            let total_len = read_u32(&header, 4, big_endian) as usize;
+            if total_len < 12 || !total_len.is_multiple_of(4) {
+                return Err(invalid_data("bad pcapng block length"));
+            }
+            // Body plus the trailing copy of the block length.
+            self.read_buf(total_len - 8)?;
+            let body = &self.buf[..total_len - 12];
+            let Format::PcapNg { interfaces, .. } = &mut self.format else {
+                unreachable!()
+            };
+            // Packet data is returned as a range of the buffer, as the buffer
+            // can only be borrowed for the caller once the loop is left.
+            let (if_id, ts, data) = match ty {
+                PCAPNG_IDB => {
+                    interfaces.push(parse_idb(body, big_endian)?);
+                    continue;
+                }
+                PCAPNG_EPB | PCAPNG_PB if body.len() >= 20 => {
+                    let cap_len = read_u32(body, 12, big_endian) as usize;
+                    if body.len() < 20 + cap_len {
+                        return Err(invalid_data("truncated packet block"));
+                    }
+                    let ts = (read_u32(body, 4, big_endian) as u64) << 32
+                        | read_u32(body, 8, big_endian) as u64;
+                    let if_id = if ty == PCAPNG_EPB {
+                        read_u32(body, 0, big_endian) as usize
+                    } else {
+                        read_u16(body, 0, big_endian) as usize
+                    };
+                    (if_id, ts, 20..20 + cap_len)
+                }
+                PCAPNG_SPB if body.len() >= 4 => {
+                    let orig_len = read_u32(body, 0, big_endian) as usize;
+                    (0, 0, 4..body.len().min(4 + orig_len))
+                }
+                _ => continue,
+            };
+            let iface = interfaces
+                .get(if_id)
+                .ok_or_else(|| invalid_data("packet for undeclared interface"))?;
+            let timestamp = Duration::new(
+                ts / iface.ts_units,
+                ((ts % iface.ts_units) as u128 * 1_000_000_000 / iface.ts_units as u128) as u32,
+            );
+            break (iface.link_type, timestamp, data);
+        };
+        Ok(Some(Record {
+            link_type,
+            timestamp,
+            data: &self.buf[data],
+        }))
+    }
+
+    fn big_endian(&self) -> bool {
+        match self.format {
+            Format::Pcap { big_endian, .. } | Format::PcapNg { big_endian, .. } => big_endian,
+        }
+    }
+
+    fn read_section_header(&mut self) -> io::Result<()> {
+        let mut header = [0u8; 8];
+        header[..4].copy_from_slice(&PCAPNG_SHB.to_le_bytes());
+        self.input.read_exact(&mut header[4..])?;
+        self.read_section_header_body(header)
+    }
+
+    /// Reads the rest of a section header block whose type and length are in `header`.
+    /// A new section resets the byte order and the interface list.
+    fn read_section_header_body(&mut self, header: [u8; 8]) -> io::Result<()> {
+        let mut magic = [0u8; 4];
+        self.input.read_exact(&mut magic)?;
+        let big_endian = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (PCAPNG_BYTE_ORDER_MAGIC, _) => false,
+            (_, PCAPNG_BYTE_ORDER_MAGIC) => true,
+            _ => return Err(invalid_data("bad pcapng byte order magic")),
+        };
+        let total_len = read_u32(&header, 4, big_endian) as usize;
+        if total_len < 28 || !total_len.is_multiple_of(4) {
+            return Err(invalid_data("bad pcapng section header length"));
+        }
+        self.read_buf(total_len - 12)?;
+        self.format = Format::PcapNg {
+            big_endian,
+            interfaces: vec![],
+        };
+        Ok(())
+    }
+
+    fn read_buf(&mut self, len: usize) -> io::Result<()> {
+        if len > MAX_RECORD_LEN {
+            return Err(invalid_data("record too large"));
+        }
+        self.buf.resize(len, 0);
+        self.input.read_exact(&mut self.buf)
+    }
+}
+
+/// Interface written to a pcapng Interface Description Block.
+#[derive(Debug, Clone)]
+pub struct InterfaceDescription {
+    pub link_type: u32,
+    pub snap_len: u32,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Capture counters written to a pcapng Interface Statistics Block.
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceStatistics {
+    pub start: Option<Duration>,
+    /// Time the counters were taken, also the timestamp of the block.
+    pub end: Duration,
+    /// Packets received from the interface, including dropped ones.
+    pub received: Option<u64>,
+    /// Packets dropped by the operating system.
+    pub os_drops: Option<u64>,
+    /// Packets delivered to the application.
+    pub delivered: Option<u64>,
+    pub comment: Option<String>,
+}
+
+/// Writer for pcapng files with nanosecond timestamps.
+///
+/// Every block is written with a single `write_all`, so a file is complete up
+/// to the last packet even if the process is killed.
+pub struct Writer<W: Write> {
+    output: W,
+    len: u64,
+}
+
+impl Writer<File> {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        interfaces: &[InterfaceDescription],
+    ) -> io::Result<Self> {
+        Self::new(File::create(path)?, interfaces)
+    }
+}
+
+impl<W: Write> Writer<W> {
+    /// Writes the section header and the description of `interfaces`, which
+    /// packets then refer to by their index.
+    pub fn new(output: W, interfaces: &[InterfaceDescription]) -> io::Result<Self> {
+        let mut writer = Self { output, len: 0 };
+        let mut shb = vec![];
+        shb.extend(PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend(1u16.to_le_bytes());
+        shb.extend(0u16.to_le_bytes());
+        shb.extend((-1i64).to_le_bytes());
+        put_option(
+            &mut shb,
+            PCAPNG_OPT_SHB_USERAPPL,
+            env!("CARGO_PKG_NAME").as_bytes(),
+        );
+        put_option(&mut shb, PCAPNG_OPT_END, &[]);
+        writer.write_block(PCAPNG_SHB, &shb)?;
+        for iface in interfaces {
+            let mut idb = vec![];
+            idb.extend((iface.link_type as u16).to_le_bytes());
+            idb.extend(0u16.to_le_bytes());
+            idb.extend(iface.snap_len.to_le_bytes());
+            put_option(&mut idb, PCAPNG_OPT_IF_NAME, iface.name.as_bytes());
+            if let Some(description) = &iface.description {
+                put_option(&mut idb, PCAPNG_OPT_IF_DESCRIPTION, description.as_bytes());
+            }
+            put_option(&mut idb, PCAPNG_OPT_IF_TSRESOL, &[9]);
+            put_option(&mut idb, PCAPNG_OPT_END, &[]);
+            writer.write_block(PCAPNG_IDB, &idb)?;
+        }
+        Ok(writer)
+    }
+
+    /// Writes a packet received on interface `if_id` at `timestamp` since the Unix epoch.
+    pub fn write_packet(&mut self, if_id: u32, timestamp: Duration, data: &[u8]) -> io::Result<()> {
+        let mut epb = Vec::with_capacity(20 + data.len() + 3);
+        epb.extend(if_id.to_le_bytes());
+        epb.extend(pcapng_timestamp(timestamp));
+        epb.extend((data.len() as u32).to_le_bytes());
+        epb.extend((data.len() as u32).to_le_bytes());
+        epb.extend(data);
+        self.write_block(PCAPNG_EPB, &epb)
+    }
+
+    /// Writes capture statistics of interface `if_id`.
+    pub fn write_statistics(&mut self, if_id: u32, stats: &InterfaceStatistics) -> io::Result<()> {
+        let mut isb = vec![];
+        isb.extend(if_id.to_le_bytes());
+        isb.extend(pcapng_timestamp(stats.end));
+        if let Some(comment) = &stats.comment {
+            put_option(&mut isb, PCAPNG_OPT_COMMENT, comment.as_bytes());
+        }
+        if let Some(start) = stats.start {
+            put_option(&mut isb, PCAPNG_OPT_ISB_STARTTIME, &pcapng_timestamp(start));
+        }
+        put_option(
+            &mut isb,
+            PCAPNG_OPT_ISB_ENDTIME,
+            &pcapng_timestamp(stats.end),
+        );
+        let counters = [
+            (PCAPNG_OPT_ISB_IFRECV, stats.received),
+            (PCAPNG_OPT_ISB_OSDROP, stats.os_drops),
+            (PCAPNG_OPT_ISB_USRDELIV, stats.delivered),
+        ];
+        for (code, value) in counters {
+            if let Some(value) = value {
+                put_option(&mut isb, code, &value.to_le_bytes());
+            }
+        }
+        put_option(&mut isb, PCAPNG_OPT_END, &[]);
+        self.write_block(PCAPNG_ISB, &isb)
+    }
+
+    /// Number of bytes written so far.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn write_block(&mut self, ty: u32, body: &[u8]) -> io::Result<()> {
+        let pad_len = (4 - body.len() % 4) % 4;
+        let total_len = (12 + body.len() + pad_len) as u32;
+        let mut block = Vec::with_capacity(total_len as usize);
+        block.extend(ty.to_le_bytes());
+        block.extend(total_len.to_le_bytes());
+        block.extend(body);
+        block.extend(&[0u8; 3][..pad_len]);
+        block.extend(total_len.to_le_bytes());
+        self.output.write_all(&block)?;
+        self.len += total_len as u64;
+        Ok(())
+    }
+}
+
+/// When a [`RotatingWriter`] moves on to a new file.
+#[derive(Debug, Clone, Default)]
+pub struct Rotation {
+    /// Start a new file once this many bytes have been written.
+    pub max_size: Option<u64>,
+    /// Start a new file once a packet is this much newer than the first one of the file.
+    pub max_age: Option<Duration>,
+    /// Reuse the oldest file after this many files, keeping a ring of files.
+    pub file_count: Option<usize>,
+}
+
+impl Rotation {
+    pub fn is_enabled(&self) -> bool {
+        self.max_size.is_some() || self.max_age.is_some()
+    }
+}
+
+/// pcapng writer that spreads packets over numbered files (`<path>.0`,
+/// `<path>.1`, ...) according to a [`Rotation`], or writes `<path>` as is when
+/// rotation is disabled.
+pub struct RotatingWriter {
+    path: PathBuf,
+    rotation: Rotation,
+    interfaces: Vec<InterfaceDescription>,
+    writer: Writer<File>,
+    index: usize,
+    started: Option<Duration>,
+}
+
+impl RotatingWriter {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        rotation: Rotation,
+        interfaces: Vec<InterfaceDescription>,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let writer = Writer::create(Self::file_path(&path, &rotation, 0), &interfaces)?;
+        Ok(Self {
+            path,
+            rotation,
+            interfaces,
+            writer,
+            index: 0,
+            started: None,
+        })
+    }
+
+    pub fn write_packet(&mut self, if_id: u32, timestamp: Duration, data: &[u8]) -> io::Result<()> {
+        let started = *self.started.get_or_insert(timestamp);
+        let too_big = self
+            .rotation
+            .max_size
+            .is_some_and(|max_size| self.writer.len() + data.len() as u64 + 32 > max_size);
+        let too_old = self
+            .rotation
+            .max_age
+            .is_some_and(|max_age| timestamp.saturating_sub(started) >= max_age);
+        if too_big || too_old {
+            self.rotate()?;
+            self.started = Some(timestamp);
+        }
+        self.writer.write_packet(if_id, timestamp, data)
+    }
+
+    /// Writes capture statistics to the current file.
+    pub fn write_statistics(&mut self, if_id: u32, stats: &InterfaceStatistics) -> io::Result<()> {
+        self.writer.write_statistics(if_id, stats)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.index += 1;
+        let mut file_idx = self.index;
+        if let Some(file_count) = self.rotation.file_count {
+            file_idx %= file_count.max(1);
+        }
+        let path = Self::file_path(&self.path, &self.rotation, file_idx);
+        self.writer = Writer::create(path, &self.interfaces)?;
+        Ok(())
+    }
+
+    fn file_path(path: &Path, rotation: &Rotation, index: usize) -> PathBuf {
+        if rotation.is_enabled() {
+            let mut path = path.as_os_str().to_owned();
+            path.push(format!(".{}", index));
+            PathBuf::from(path)
+        } else {
+            path.to_owned()
+        }
+    }
+}
+
+/// Splits a nanosecond timestamp into its high and low 32 bits.
+fn pcapng_timestamp(timestamp: Duration) -> [u8; 8] {
+    let ts = timestamp.as_nanos() as u64;
+    let mut buf = [0; 8];
+    buf[..4].copy_from_slice(&((ts >> 32) as u32).to_le_bytes());
+    buf[4..].copy_from_slice(&(ts as u32).to_le_bytes());
+    buf
+}
+
+fn put_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend(code.to_le_bytes());
+    buf.extend((value.len() as u16).to_le_bytes());
+    buf.extend(value);
+    buf.resize(buf.len() + (4 - value.len() % 4) % 4, 0);
+}
+
+fn parse_idb(body: &[u8], big_endian: bool) -> io::Result<Interface> {
+    if body.len() < 8 {
+        return Err(invalid_data("truncated interface description block"));
+    }
+    let mut iface = Interface {
+        link_type: read_u16(body, 0, big_endian) as u32,
+        ts_units: 1_000_000,
+    };
+    let mut options = &body[8..];
+    while options.len() >= 4 {
+        let code = read_u16(options, 0, big_endian);
+        let len = read_u16(options, 2, big_endian) as usize;
+        let value = options.get(4..4 + len).unwrap_or_default();
+        match code {
+            PCAPNG_OPT_END => break,
+            PCAPNG_OPT_IF_TSRESOL => {
+                if let Some(&resol) = value.first() {
+                    let exp = (resol & 0x7f) as u32;
+                    iface.ts_units = if resol & 0x80 != 0 {
+                        2u64.checked_pow(exp)
+                    } else {
+                        10u64.checked_pow(exp)
+                    }
+                    .filter(|&units| units != 0)
+                    .ok_or_else(|| invalid_data("bad if_tsresol option"))?;
+                }
+            }
+            _ => (),
+        }
+        options = options.get(4 + len.div_ceil(4) * 4..).unwrap_or_default();
+    }
+    Ok(iface)
+}
+
+/// Fills `buf`, returning `false` if the input ended before its first byte.
+fn read_exact_or_eof<R: Read>(input: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(Error::from(ErrorKind::UnexpectedEof)),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(true)
+}
+
+fn read_u16(buf: &[u8], offset: usize, big_endian: bool) -> u16 {
+    let bytes = [buf[offset], buf[offset + 1]];
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize, big_endian: bool) -> u32 {
+    let bytes = [
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interface(link_type: u32) -> InterfaceDescription {
+        InterfaceDescription {
+            link_type,
+            snap_len: 65535,
+            name: "mon0".to_owned(),
+            description: None,
+        }
+    }
+
+    /// Reads every record as (link type, timestamp, data).
+    fn read_all(file: &[u8]) -> io::Result<Vec<(u32, Duration, Vec<u8>)>> {
+        let mut reader = Reader::new(file)?;
+        let mut records = vec![];
+        while let Some(record) = reader.next_record()? {
+            records.push((record.link_type, record.timestamp, record.data.to_vec()));
+        }
+        Ok(records)
+    }
+
+    /// Classic pcap file with one record, in the byte order given by `u32_bytes`.
+    fn legacy_pcap(magic: u32, u32_bytes: fn(u32) -> [u8; 4], frac: u32) -> Vec<u8> {
+        let mut file = vec![];
+        file.extend(u32_bytes(magic));
+        // Version 2.4, time zone and accuracy.
+        file.extend(u32_bytes(2 << 16 | 4));
+        file.extend(u32_bytes(0));
+        file.extend(u32_bytes(0));
+        file.extend(u32_bytes(65535));
+        file.extend(u32_bytes(LINKTYPE_IEEE802_11));
+        file.extend(u32_bytes(1_700_000_000));
+        file.extend(u32_bytes(frac));
+        file.extend(u32_bytes(3));
+        file.extend(u32_bytes(3));
+        file.extend([1, 2, 3]);
+        file
+    }
+
+    fn block(ty: u32, body: &[u8]) -> Vec<u8> {
+        let mut writer = Writer {
+            output: vec![],
+            len: 0,
+        };
+        writer.write_block(ty, body).unwrap();
+        writer.output
+    }
+
+    /// pcapng file with one interface of timestamp resolution `tsresol`.
+    fn pcapng(tsresol: u8) -> Vec<u8> {
+        let mut shb = vec![];
+        shb.extend(PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend([1, 0, 0, 0]);
+        shb.extend((-1i64).to_le_bytes());
+        let mut idb = vec![];
+        idb.extend((LINKTYPE_IEEE802_11_RADIOTAP as u16).to_le_bytes());
+        idb.extend([0, 0, 0, 0, 0, 0]);
+        put_option(&mut idb, PCAPNG_OPT_IF_TSRESOL, &[tsresol]);
+        put_option(&mut idb, PCAPNG_OPT_END, &[]);
+        [block(PCAPNG_SHB, &shb), block(PCAPNG_IDB, &idb)].concat()
+    }
+
+    fn epb(ts: u64, data: &[u8]) -> Vec<u8> {
+        let mut epb = vec![0; 4];
+        epb.extend(((ts >> 32) as u32).to_le_bytes());
+        epb.extend((ts as u32).to_le_bytes());
+        epb.extend((data.len() as u32).to_le_bytes());
+        epb.extend((data.len() as u32).to_le_bytes());
+        epb.extend(data);
+        block(PCAPNG_EPB, &epb)
+    }
+
+    #[test]
+    fn writer_round_trip() {
+        let mut writer = Writer::new(
+            vec![],
+            &[
+                interface(LINKTYPE_IEEE802_11_RADIOTAP),
+                interface(LINKTYPE_PPI),
+            ],
+        )
+        .unwrap();
+        let first = Duration::new(1_700_000_000, 123_456_789);
+        let second = first + Duration::from_nanos(1);
+        writer.write_packet(0, first, &[1, 2, 3, 4, 5]).unwrap();
+        writer
+            .write_statistics(
+                0,
+                &InterfaceStatistics {
+                    end: second,
+                    received: Some(2),
+                    comment: Some("stats".to_owned()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        writer.write_packet(1, second, &[6]).unwrap();
+        assert_eq!(writer.len(), writer.output.len() as u64);
+        assert_eq!(
+            read_all(&writer.output).unwrap(),
+            [
+                (LINKTYPE_IEEE802_11_RADIOTAP, first, vec![1, 2, 3, 4, 5]),
+                (LINKTYPE_PPI, second, vec![6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_microseconds() {
+        for u32_bytes in [u32::to_le_bytes, u32::to_be_bytes] {
+            let file = legacy_pcap(PCAP_MAGIC_USEC, u32_bytes, 250_000);
+            let reader = Reader::new(file.as_slice()).unwrap();
+            assert_eq!(reader.link_type(), Some(LINKTYPE_IEEE802_11));
+            assert_eq!(
+                read_all(&file).unwrap(),
+                [(
+                    LINKTYPE_IEEE802_11,
+                    Duration::new(1_700_000_000, 250_000_000),
+                    vec![1, 2, 3]
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn legacy_nanoseconds() {
+        for u32_bytes in [u32::to_le_bytes, u32::to_be_bytes] {
+            let file = legacy_pcap(PCAP_MAGIC_NSEC, u32_bytes, 250_000);
+            assert_eq!(
+                read_all(&file).unwrap()[0].1,
+                Duration::new(1_700_000_000, 250_000)
+            );
+        }
+    }
+
+    #[test]
+    fn pcapng_timestamp_resolution() {
+        let cases = [
+            (6, 1_500_000, Duration::from_micros(1_500_000)),
+            // Picoseconds.
+            (12, 1_500_000_000_000, Duration::from_millis(1_500)),
+            // 2^-10 s.
+            (0x80 | 10, 1536, Duration::from_millis(1_500)),
+        ];
+        for (tsresol, ts, timestamp) in cases {
+            let file = [pcapng(tsresol), epb(ts, &[7])].concat();
+            assert_eq!(read_all(&file).unwrap()[0].1, timestamp);
+        }
+        let file = [pcapng(0x7f), epb(0, &[7])].concat();
+        assert!(read_all(&file).is_err());
+    }
+
+    #[test]
+    fn simple_packet_block() {
+        let mut spb = 5u32.to_le_bytes().to_vec();
+        spb.extend([1, 2, 3, 4, 5]);
+        let file = [pcapng(9), block(PCAPNG_SPB, &spb)].concat();
+        assert_eq!(
+            read_all(&file).unwrap(),
+            [(
+                LINKTYPE_IEEE802_11_RADIOTAP,
+                Duration::ZERO,
+                vec![1, 2, 3, 4, 5]
+            )]
+        );
+    }
+
+    #[test]
+    fn truncated_records() {
+        let file = legacy_pcap(PCAP_MAGIC_USEC, u32::to_le_bytes, 0);
+        let err = read_all(&file[..file.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = read_all(&file[..30]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let file = [pcapng(9), epb(0, &[1, 2, 3])].concat();
+        assert!(read_all(&file[..file.len() - 4]).is_err());
+        // Packet for an interface that was never described.
+        let header = pcapng(9);
+        let mut file = [header.as_slice(), &epb(0, &[1])].concat();
+        file[header.len() + 8] = 1;
+        assert_eq!(read_all(&file).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert!(read_all(&[]).is_err());
+    }
+
+    #[test]
+    fn mutated_files() {
+        let mut writer = Writer::new(vec![], &[interface(LINKTYPE_IEEE802_11_RADIOTAP)]).unwrap();
+        writer
+            .write_packet(0, Duration::new(1, 2), &[1, 2, 3, 4, 5])
+            .unwrap();
+        let files = [
+            writer.output,
+            legacy_pcap(PCAP_MAGIC_NSEC, u32::to_be_bytes, 0),
+        ];
+        // No single byte value anywhere in a file makes the reader panic.
+        for file in files {
+            for idx in 0..file.len() {
+                for value in [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff] {
+                    let mut file = file.clone();
+                    file[idx] = value;
+                    let _ = read_all(&file);
+                }
+                let _ = read_all(&file[..idx]);
+            }
+        }
+    }
+}
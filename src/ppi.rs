@@ -0,0 +1,77 @@
+use crate::misc::ParseError;
+use crate::pcap::LINKTYPE_IEEE802_11;
+use crate::radiotap::{AntennaNoise, AntennaSignal, Channel, Field, Flags, Rate, Summary, TSFT};
+
+const FIELD_80211_COMMON: u16 = 2;
+
+const COMMON_FCS: u16 = 0x0001;
+const COMMON_FCS_INVALID: u16 = 0x0004;
+
+/// Parses a PPI (Per-Packet Information) header, returning the radio
+/// information of its 802.11-Common field and the 802.11 frame that follows.
+pub fn parse(packet: &[u8]) -> Result<(Summary, &[u8]), ParseError> {
+    let [_version, _flags, l0, l1, d0, d1, d2, d3, ..] = *packet else {
+        return Err(ParseError::Truncated {
+            need: 8,
+            have: packet.len(),
+        });
+    };
+    let len = u16::from_le_bytes([l0, l1]) as usize;
+    let dlt = u32::from_le_bytes([d0, d1, d2, d3]);
+    if len < 8 || len > packet.len() {
+        return Err(ParseError::BadPPILength(len));
+    }
+    if dlt != LINKTYPE_IEEE802_11 {
+        return Err(ParseError::UnsupportedLinkType(dlt));
+    }
+    let mut summary = Summary::default();
+    let mut fields = &packet[8..len];
+    while let [t0, t1, l0, l1, ref rest @ ..] = *fields {
+        let ty = u16::from_le_bytes([t0, t1]);
+        let len = u16::from_le_bytes([l0, l1]) as usize;
+        let data = rest.get(..len).ok_or(ParseError::Truncated {
+            need: len,
+            have: rest.len(),
+        })?;
+        if ty == FIELD_80211_COMMON {
+            parse_common(data, &mut summary)?;
+        }
+        fields = &rest[len..];
+    }
+    Ok((summary, &packet[len..]))
+}
+
+fn parse_common(data: &[u8], summary: &mut Summary) -> Result<(), ParseError> {
+    let [t0, t1, t2, t3, t4, t5, t6, t7, fl0, fl1, r0, _r1, c0, c1, c2, c3, _, _, signal, noise, ..] =
+        *data
+    else {
+        return Err(ParseError::Truncated {
+            need: 20,
+            have: data.len(),
+        });
+    };
+    let ppi_flags = u16::from_le_bytes([fl0, fl1]);
+    let mut flags = 0;
+    if ppi_flags & COMMON_FCS != 0 {
+        flags |= Flags::FCS;
+    }
+    if ppi_flags & COMMON_FCS_INVALID != 0 {
+        flags |= Flags::BAD_FCS;
+    }
+    // The 802.11-Common field uses the radiotap encodings, so the radiotap decoders apply.
+    summary.tsft = TSFT::try_from(Field::TSFT(&[t0, t1, t2, t3, t4, t5, t6, t7])).ok();
+    summary.flags = Flags::try_from(Field::Flags(&[flags])).ok();
+    if r0 != 0 {
+        summary.rate = Rate::try_from(Field::Rate(&[r0])).ok();
+    }
+    if [c0, c1] != [0, 0] {
+        summary.channel = Channel::try_from(Field::Channel(&[c0, c1, c2, c3])).ok();
+    }
+    if signal != 0 {
+        summary.signal = AntennaSignal::try_from(Field::AntennaSignal(&[signal])).ok();
+    }
+    if noise != 0 {
+        summary.noise = AntennaNoise::try_from(Field::AntennaNoise(&[noise])).ok();
+    }
+    Ok(())
+}
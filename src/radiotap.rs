@@ -1,4 +1,5 @@
-use crate::misc::MemCast;
+use crate::misc::{MemCast, ParseError};
+use core::fmt::{self, Display, Formatter};
 
 #[repr(C, packed)]
 struct Header {
@@ -8,80 +9,300 @@ struct Header {
     present: [u8; 4],
 }
 
+#[allow(clippy::len_without_is_empty)]
 pub trait RadioTap {
-    fn version(&self) -> u8;
-    fn len(&self) -> usize;
-    fn iter(&self) -> Iter;
+    fn version(&self) -> Result<u8, ParseError>;
+    fn len(&self) -> Result<usize, ParseError>;
+    fn iter(&self) -> Result<Iter<'_>, ParseError>;
 }
 
 impl RadioTap for [u8] {
-    fn version(&self) -> u8 {
-        self.cast_ref::<Header>().version
+    fn version(&self) -> Result<u8, ParseError> {
+        Ok(self.try_cast_ref::<Header>()?.version)
     }
 
-    fn len(&self) -> usize {
-        u16::from_le_bytes(self.cast_ref::<Header>().len) as usize
+    fn len(&self) -> Result<usize, ParseError> {
+        let len = u16::from_le_bytes(self.try_cast_ref::<Header>()?.len) as usize;
+        if len < core::mem::size_of::<Header>() || len > <[u8]>::len(self) {
+            return Err(ParseError::BadRadioTapLength(len));
+        }
+        Ok(len)
     }
 
-    fn iter(&self) -> Iter {
-        let len = self.len();
+    fn iter(&self) -> Result<Iter<'_>, ParseError> {
+        let len = RadioTap::len(self)?;
         Iter::new(&self[..len])
     }
 }
 
+/// Iterates over the fields of a radiotap header.
+///
+/// All chained present words are walked. A set namespace bit (29) starts a new
+/// radiotap namespace, which drivers use to report per-antenna fields; the
+/// antenna index of the namespace being walked is available from [`Iter::antenna`].
+/// A set vendor namespace bit (30) is returned as [`Field::Vendor`] and the
+/// vendor data is skipped by its declared length, so the fields of any
+/// namespace following it stay in sync.
 #[derive(Debug)]
 pub struct Iter<'a> {
     rtap: &'a [u8],
     fields: &'a [u8],
-    present: u32,
-    idx: usize,
+    present: &'a [u8],
+    word: usize,
+    ns_word: usize,
+    bit: usize,
+    ns: Namespace,
+    namespace: usize,
+    antenna: Option<u8>,
+    done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Namespace {
+    RadioTap,
+    Vendor,
 }
 
 impl<'a> Iter<'a> {
-    fn new(rtap: &'a [u8]) -> Self {
-        let present_ext_count = rtap[4..]
-            .chunks(4)
-            .take_while(|x| u32::from_le_bytes(*x.cast_ref::<[u8; 4]>()) & 1 << 31 != 0)
-            .count();
-        Self {
+    const BIT_TLV: usize = 28;
+    const BIT_RADIOTAP_NS: usize = 29;
+    const BIT_VENDOR_NS: usize = 30;
+    const BIT_EXT: usize = 31;
+
+    fn new(rtap: &'a [u8]) -> Result<Self, ParseError> {
+        rtap.try_cast_ref::<Header>()?;
+        let mut present_count = 0;
+        let mut terminated = false;
+        for word in rtap[4..].chunks(4) {
+            if word.len() < 4 {
+                return Err(ParseError::BadRadioTapLength(rtap.len()));
+            }
+            present_count += 1;
+            if word[3] & 0x80 == 0 {
+                terminated = true;
+                break;
+            }
+        }
+        // The last present word must clear the extension bit within the header.
+        if !terminated {
+            return Err(ParseError::BadRadioTapLength(rtap.len()));
+        }
+        let fields_offset = 4 + 4 * present_count;
+        if fields_offset > rtap.len() {
+            return Err(ParseError::BadRadioTapLength(rtap.len()));
+        }
+        let mut iter = Self {
             rtap,
-            fields: &rtap[8 + 4 * present_ext_count..],
-            present: u32::from_le_bytes(rtap.cast_ref::<Header>().present),
-            idx: 0,
+            fields: &rtap[fields_offset..],
+            present: &rtap[4..fields_offset],
+            word: 0,
+            ns_word: 0,
+            bit: 0,
+            ns: Namespace::RadioTap,
+            namespace: 0,
+            antenna: None,
+            done: false,
+        };
+        iter.antenna = iter.peek_antenna();
+        Ok(iter)
+    }
+
+    /// Index of the radiotap namespace the last returned field belongs to.
+    /// Namespace 0 carries the combined values, later ones are per antenna.
+    pub fn namespace(&self) -> usize {
+        self.namespace
+    }
+
+    /// Returns `true` while walking the present words of a vendor namespace.
+    pub fn in_vendor_namespace(&self) -> bool {
+        self.ns == Namespace::Vendor
+    }
+
+    /// Antenna index from the Antenna field of the current namespace, if present.
+    pub fn antenna(&self) -> Option<u8> {
+        self.antenna
+    }
+
+    fn present_word(&self, word: usize) -> u32 {
+        let bytes = &self.present[4 * word..4 * word + 4];
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn offset(&self) -> usize {
+        self.fields.as_ptr() as usize - self.rtap.as_ptr() as usize
+    }
+
+    /// Walks the current namespace's first present word up to the Antenna field
+    /// without consuming anything, so fields preceding it can be tagged.
+    fn peek_antenna(&self) -> Option<u8> {
+        if 4 * self.word + 4 > self.present.len() {
+            return None;
+        }
+        let present = self.present_word(self.word);
+        let antenna_idx = 11;
+        if present & 1 << antenna_idx == 0 {
+            return None;
         }
+        let mut offset = self.offset();
+        for (idx, field) in FIELDS.iter().enumerate().take(antenna_idx) {
+            if present & 1 << idx != 0 {
+                offset = align(offset, field.alignment) + field.size;
+            }
+        }
+        self.rtap
+            .get(align(offset, FIELDS[antenna_idx].alignment))
+            .copied()
+    }
+
+    /// Moves to the next present word, switching namespace as requested by the
+    /// namespace bits of the current one. Returns `false` when there is none.
+    fn next_word(&mut self) -> bool {
+        let present = self.present_word(self.word);
+        if present & 1 << Self::BIT_EXT == 0 || 4 * (self.word + 1) >= self.present.len() {
+            return false;
+        }
+        self.word += 1;
+        self.bit = 0;
+        if present & 1 << Self::BIT_VENDOR_NS != 0 {
+            self.ns_word = 0;
+            self.ns = Namespace::Vendor;
+            self.antenna = None;
+        } else if present & 1 << Self::BIT_RADIOTAP_NS != 0 {
+            self.ns_word = 0;
+            self.ns = Namespace::RadioTap;
+            self.namespace += 1;
+            self.antenna = self.peek_antenna();
+        } else {
+            self.ns_word += 1;
+        }
+        true
+    }
+
+    /// Reads the vendor namespace field together with the vendor data it
+    /// announces, which is consumed as a whole.
+    fn next_vendor(&mut self) -> Result<Field<'a>, ParseError> {
+        let pad_len = align(self.offset(), 2) - self.offset();
+        let rest = self.fields.get(pad_len..).unwrap_or_default();
+        let [o0, o1, o2, sub_namespace, l0, l1, ..] = *rest else {
+            return Err(ParseError::Truncated {
+                need: pad_len + 6,
+                have: self.fields.len(),
+            });
+        };
+        let skip_len = u16::from_le_bytes([l0, l1]) as usize;
+        let Some(data) = rest.get(6..6 + skip_len) else {
+            return Err(ParseError::Truncated {
+                need: pad_len + 6 + skip_len,
+                have: self.fields.len(),
+            });
+        };
+        self.fields = &rest[6 + skip_len..];
+        Ok(Field::Vendor {
+            oui: [o0, o1, o2],
+            sub_namespace,
+            data,
+        })
+    }
+
+    fn next_field(&mut self, idx: usize) -> Result<Field<'a>, ParseError> {
+        let field = &FIELDS[idx];
+        let pad_len = align(self.offset(), field.alignment) - self.offset();
+        let len = pad_len + field.size;
+        if self.fields.len() < len {
+            return Err(ParseError::Truncated {
+                need: len,
+                have: self.fields.len(),
+            });
+        }
+        let (bytes, rest) = self.fields.split_at(len);
+        self.fields = rest;
+        Ok((field.fn_new)(&bytes[pad_len..]))
+    }
+
+    fn next_tlv(&mut self) -> Option<Result<Field<'a>, ParseError>> {
+        let pad_len = align(self.offset(), 4) - self.offset();
+        let rest = self.fields.get(pad_len..).unwrap_or_default();
+        let [t0, t1, l0, l1, ..] = *rest else {
+            return None;
+        };
+        let ty = u16::from_le_bytes([t0, t1]);
+        let len = u16::from_le_bytes([l0, l1]) as usize;
+        let Some(data) = rest.get(4..4 + len) else {
+            return Some(Err(ParseError::Truncated {
+                need: 4 + len,
+                have: rest.len(),
+            }));
+        };
+        self.fields = &rest[4 + len..];
+        Some(Ok(match ty {
+            Field::TLV_S1G => Field::S1G(data),
+            Field::TLV_USIG => Field::USIG(data),
+            Field::TLV_EHT => Field::EHT(data),
+            _ => Field::TLV { ty, data },
+        }))
     }
 }
 
 impl<'a> Iterator for Iter<'a> {
-    type Item = Field<'a>;
+    type Item = Result<Field<'a>, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.idx >= FIELDS.len() || self.idx >= 29 {
-            return None;
-        }
-        if self.present & 1 << self.idx != 0 {
-            let field = &FIELDS[self.idx];
-            let pad_len = (self.fields.as_ptr() as usize - self.rtap.as_ptr() as usize)
-                & (field.alignment - 1);
-            let len = pad_len + field.size;
-            let (bytes, rest) = self.fields.split_at(len);
-            self.idx += 1;
-            self.fields = rest;
-            Some((field.fn_new)(&bytes[pad_len..]))
-        } else {
-            self.idx += 1;
-            self.next()
+        while !self.done {
+            let present = self.present_word(self.word);
+            if self.bit == Self::BIT_RADIOTAP_NS {
+                self.bit = Self::BIT_VENDOR_NS + 1;
+                if present & 1 << Self::BIT_VENDOR_NS != 0 {
+                    let vendor = self.next_vendor();
+                    self.done = vendor.is_err();
+                    return Some(vendor);
+                }
+                continue;
+            }
+            if self.bit > Self::BIT_RADIOTAP_NS {
+                if !self.next_word() {
+                    self.done = true;
+                }
+                continue;
+            }
+            let idx = 32 * self.ns_word + self.bit;
+            self.bit += 1;
+            // Vendor fields are covered by the skip length of the vendor namespace.
+            if self.ns == Namespace::Vendor || present & 1 << (idx % 32) == 0 {
+                continue;
+            }
+            if idx == Self::BIT_TLV {
+                // TLVs take up the remainder of the header, so nothing follows them.
+                let tlv = self.next_tlv();
+                self.bit = Self::BIT_TLV;
+                if !matches!(tlv, Some(Ok(_))) {
+                    self.done = true;
+                }
+                return tlv;
+            }
+            if idx >= FIELDS.len() {
+                // The size of an unknown field is unknown, so nothing after it can be parsed.
+                self.done = true;
+                return None;
+            }
+            let field = self.next_field(idx);
+            self.done = field.is_err();
+            return Some(field);
         }
+        None
     }
 }
 
+fn align(offset: usize, alignment: usize) -> usize {
+    (offset + alignment - 1) & !(alignment - 1)
+}
+
 struct FieldDef {
     size: usize,
     alignment: usize,
     fn_new: fn(bytes: &[u8]) -> Field,
 }
 
-const FIELDS: [FieldDef; 6] = [
+const FIELDS: [FieldDef; 28] = [
     FieldDef {
         size: 8,
         alignment: 8,
@@ -112,9 +333,119 @@ const FIELDS: [FieldDef; 6] = [
         alignment: 1,
         fn_new: |bytes| Field::AntennaSignal(bytes),
     },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::AntennaNoise(bytes),
+    },
+    FieldDef {
+        size: 2,
+        alignment: 2,
+        fn_new: |bytes| Field::LockQuality(bytes),
+    },
+    FieldDef {
+        size: 2,
+        alignment: 2,
+        fn_new: |bytes| Field::TxAttenuation(bytes),
+    },
+    FieldDef {
+        size: 2,
+        alignment: 2,
+        fn_new: |bytes| Field::DbTxAttenuation(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::DbmTxPower(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::Antenna(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::DbAntennaSignal(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::DbAntennaNoise(bytes),
+    },
+    FieldDef {
+        size: 2,
+        alignment: 2,
+        fn_new: |bytes| Field::RxFlags(bytes),
+    },
+    FieldDef {
+        size: 2,
+        alignment: 2,
+        fn_new: |bytes| Field::TxFlags(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::RtsRetries(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::DataRetries(bytes),
+    },
+    FieldDef {
+        size: 8,
+        alignment: 4,
+        fn_new: |bytes| Field::XChannel(bytes),
+    },
+    FieldDef {
+        size: 3,
+        alignment: 1,
+        fn_new: |bytes| Field::MCS(bytes),
+    },
+    FieldDef {
+        size: 8,
+        alignment: 4,
+        fn_new: |bytes| Field::AMPDUStatus(bytes),
+    },
+    FieldDef {
+        size: 12,
+        alignment: 2,
+        fn_new: |bytes| Field::VHT(bytes),
+    },
+    FieldDef {
+        size: 12,
+        alignment: 8,
+        fn_new: |bytes| Field::Timestamp(bytes),
+    },
+    FieldDef {
+        size: 12,
+        alignment: 2,
+        fn_new: |bytes| Field::HE(bytes),
+    },
+    FieldDef {
+        size: 12,
+        alignment: 2,
+        fn_new: |bytes| Field::HEMU(bytes),
+    },
+    FieldDef {
+        size: 6,
+        alignment: 2,
+        fn_new: |bytes| Field::HEMUOtherUser(bytes),
+    },
+    FieldDef {
+        size: 1,
+        alignment: 1,
+        fn_new: |bytes| Field::ZeroLengthPSDU(bytes),
+    },
+    FieldDef {
+        size: 4,
+        alignment: 2,
+        fn_new: |bytes| Field::LSIG(bytes),
+    },
 ];
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub enum Field<'a> {
     TSFT(&'a [u8]),
@@ -123,41 +454,185 @@ pub enum Field<'a> {
     Channel(&'a [u8]),
     FHSS(&'a [u8]),
     AntennaSignal(&'a [u8]),
+    AntennaNoise(&'a [u8]),
+    LockQuality(&'a [u8]),
+    TxAttenuation(&'a [u8]),
+    DbTxAttenuation(&'a [u8]),
+    DbmTxPower(&'a [u8]),
+    Antenna(&'a [u8]),
+    DbAntennaSignal(&'a [u8]),
+    DbAntennaNoise(&'a [u8]),
+    RxFlags(&'a [u8]),
+    TxFlags(&'a [u8]),
+    RtsRetries(&'a [u8]),
+    DataRetries(&'a [u8]),
+    XChannel(&'a [u8]),
+    MCS(&'a [u8]),
+    AMPDUStatus(&'a [u8]),
+    VHT(&'a [u8]),
+    Timestamp(&'a [u8]),
+    HE(&'a [u8]),
+    HEMU(&'a [u8]),
+    HEMUOtherUser(&'a [u8]),
+    ZeroLengthPSDU(&'a [u8]),
+    LSIG(&'a [u8]),
+    S1G(&'a [u8]),
+    USIG(&'a [u8]),
+    EHT(&'a [u8]),
+    TLV {
+        ty: u16,
+        data: &'a [u8],
+    },
+    Vendor {
+        oui: [u8; 3],
+        sub_namespace: u8,
+        data: &'a [u8],
+    },
+}
+
+impl Field<'_> {
+    pub const TLV_S1G: u16 = 32;
+    pub const TLV_USIG: u16 = 33;
+    pub const TLV_EHT: u16 = 34;
 }
 
 #[derive(Debug)]
 pub struct InvalidField {}
 
-#[derive(Debug)]
-pub struct AntennaSignal(i8);
+macro_rules! scalar_field {
+    ($($name:ident($ty:ty) => $getter:ident;)*) => ($(
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name($ty);
 
-impl AntennaSignal {
-    pub fn dbm(&self) -> i8 {
-        self.0
+        impl $name {
+            pub fn $getter(&self) -> $ty {
+                self.0
+            }
+        }
+
+        impl TryFrom<Field<'_>> for $name {
+            type Error = InvalidField;
+
+            fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+                match value {
+                    Field::$name(bytes) => Ok($name(<$ty>::from_le_bytes(
+                        bytes.try_into().map_err(|_| InvalidField {})?,
+                    ))),
+                    _ => Err(InvalidField {}),
+                }
+            }
+        }
+    )*)
+}
+
+scalar_field! {
+    TSFT(u64) => micros;
+    Flags(u8) => bits;
+    Rate(u8) => units_500kbps;
+    AntennaSignal(i8) => dbm;
+    AntennaNoise(i8) => dbm;
+    LockQuality(u16) => quality;
+    TxAttenuation(u16) => steps;
+    DbTxAttenuation(u16) => db;
+    DbmTxPower(i8) => dbm;
+    Antenna(u8) => index;
+    DbAntennaSignal(u8) => db;
+    DbAntennaNoise(u8) => db;
+    RxFlags(u16) => bits;
+    TxFlags(u16) => bits;
+    RtsRetries(u8) => count;
+    DataRetries(u8) => count;
+    ZeroLengthPSDU(u8) => kind;
+}
+
+impl Flags {
+    pub const CFP: u8 = 0x01;
+    pub const SHORT_PREAMBLE: u8 = 0x02;
+    pub const WEP: u8 = 0x04;
+    pub const FRAGMENTATION: u8 = 0x08;
+    pub const FCS: u8 = 0x10;
+    pub const DATA_PAD: u8 = 0x20;
+    pub const BAD_FCS: u8 = 0x40;
+    pub const SHORT_GI: u8 = 0x80;
+
+    pub fn has_fcs(&self) -> bool {
+        self.0 & Self::FCS != 0
+    }
+
+    pub fn is_short_preamble(&self) -> bool {
+        self.0 & Self::SHORT_PREAMBLE != 0
+    }
+
+    pub fn is_wep(&self) -> bool {
+        self.0 & Self::WEP != 0
+    }
+
+    pub fn is_fragmented(&self) -> bool {
+        self.0 & Self::FRAGMENTATION != 0
+    }
+
+    pub fn has_data_pad(&self) -> bool {
+        self.0 & Self::DATA_PAD != 0
+    }
+
+    pub fn is_bad_fcs(&self) -> bool {
+        self.0 & Self::BAD_FCS != 0
+    }
+
+    pub fn is_short_gi(&self) -> bool {
+        self.0 & Self::SHORT_GI != 0
     }
 }
 
-impl<'a> TryFrom<Field<'a>> for AntennaSignal {
-    type Error = InvalidField;
+impl Rate {
+    pub fn kbps(&self) -> u32 {
+        self.0 as u32 * 500
+    }
+}
 
-    fn try_from(value: Field<'a>) -> Result<Self, Self::Error> {
-        match value {
-            Field::AntennaSignal(bytes) => Ok(AntennaSignal(bytes[0] as i8)),
-            _ => Err(InvalidField {}),
-        }
+impl RxFlags {
+    pub const BAD_PLCP: u16 = 0x0002;
+
+    pub fn is_bad_plcp(&self) -> bool {
+        self.0 & Self::BAD_PLCP != 0
     }
 }
 
-#[derive(Debug)]
+impl TxFlags {
+    pub const FAIL: u16 = 0x0001;
+    pub const CTS: u16 = 0x0002;
+    pub const RTS: u16 = 0x0004;
+    pub const NO_ACK: u16 = 0x0008;
+    pub const NO_SEQ: u16 = 0x0010;
+    pub const ORDER: u16 = 0x0020;
+
+    pub fn is_failed(&self) -> bool {
+        self.0 & Self::FAIL != 0
+    }
+
+    pub fn is_no_ack(&self) -> bool {
+        self.0 & Self::NO_ACK != 0
+    }
+
+    pub fn is_no_seq(&self) -> bool {
+        self.0 & Self::NO_SEQ != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Channel {
     frequency: u16,
-    _flags: u16,
+    flags: ChannelFlags,
 }
 
 impl Channel {
     pub fn frequency_mhz(&self) -> u16 {
         self.frequency
     }
+
+    pub fn flags(&self) -> ChannelFlags {
+        self.flags
+    }
 }
 
 impl TryFrom<Field<'_>> for Channel {
@@ -165,11 +640,1016 @@ impl TryFrom<Field<'_>> for Channel {
 
     fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
         match value {
-            Field::Channel(bytes) => Ok(Channel {
-                frequency: u16::from_le_bytes(*bytes.cast_ref::<[u8; 2]>()),
-                _flags: u16::from_le_bytes(*bytes[2..].cast_ref::<[u8; 2]>()),
+            Field::Channel(&[f0, f1, fl0, fl1, ..]) => Ok(Channel {
+                frequency: u16::from_le_bytes([f0, f1]),
+                flags: ChannelFlags(u16::from_le_bytes([fl0, fl1])),
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelFlags(u16);
+
+impl ChannelFlags {
+    pub const TURBO: u16 = 0x0010;
+    pub const CCK: u16 = 0x0020;
+    pub const OFDM: u16 = 0x0040;
+    pub const GHZ_2: u16 = 0x0080;
+    pub const GHZ_5: u16 = 0x0100;
+    pub const PASSIVE: u16 = 0x0200;
+    pub const DYNAMIC: u16 = 0x0400;
+    pub const GFSK: u16 = 0x0800;
+    pub const GSM: u16 = 0x1000;
+    pub const STATIC_TURBO: u16 = 0x2000;
+    pub const HALF_RATE: u16 = 0x4000;
+    pub const QUARTER_RATE: u16 = 0x8000;
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn is_2ghz(&self) -> bool {
+        self.0 & Self::GHZ_2 != 0
+    }
+
+    pub fn is_5ghz(&self) -> bool {
+        self.0 & Self::GHZ_5 != 0
+    }
+
+    pub fn is_ofdm(&self) -> bool {
+        self.0 & Self::OFDM != 0
+    }
+
+    pub fn is_cck(&self) -> bool {
+        self.0 & Self::CCK != 0
+    }
+
+    pub fn is_turbo(&self) -> bool {
+        self.0 & (Self::TURBO | Self::STATIC_TURBO) != 0
+    }
+
+    pub fn is_passive(&self) -> bool {
+        self.0 & Self::PASSIVE != 0
+    }
+
+    pub fn is_half_rate(&self) -> bool {
+        self.0 & Self::HALF_RATE != 0
+    }
+
+    pub fn is_quarter_rate(&self) -> bool {
+        self.0 & Self::QUARTER_RATE != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FHSS {
+    pub hop_set: u8,
+    pub hop_pattern: u8,
+}
+
+impl TryFrom<Field<'_>> for FHSS {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::FHSS(&[hop_set, hop_pattern, ..]) => Ok(FHSS {
+                hop_set,
+                hop_pattern,
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct XChannel {
+    pub flags: ChannelFlags,
+    pub frequency: u16,
+    pub channel: u8,
+    pub max_power: u8,
+}
+
+impl TryFrom<Field<'_>> for XChannel {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::XChannel(&[fl0, fl1, _, _, f0, f1, channel, max_power, ..]) => Ok(XChannel {
+                flags: ChannelFlags(u16::from_le_bytes([fl0, fl1])),
+                frequency: u16::from_le_bytes([f0, f1]),
+                channel,
+                max_power,
             }),
             _ => Err(InvalidField {}),
         }
     }
 }
+
+/// HT (802.11n) rate information.
+#[derive(Debug, Clone, Copy)]
+pub struct MCS {
+    known: u8,
+    flags: u8,
+    index: u8,
+}
+
+impl MCS {
+    pub const KNOWN_BW: u8 = 0x01;
+    pub const KNOWN_MCS: u8 = 0x02;
+    pub const KNOWN_GI: u8 = 0x04;
+    pub const KNOWN_FORMAT: u8 = 0x08;
+    pub const KNOWN_FEC: u8 = 0x10;
+    pub const KNOWN_STBC: u8 = 0x20;
+
+    pub fn index(&self) -> Option<u8> {
+        (self.known & Self::KNOWN_MCS != 0).then_some(self.index)
+    }
+
+    /// Number of spatial streams implied by the MCS index.
+    pub fn nss(&self) -> Option<u8> {
+        self.index().map(|index| index / 8 + 1)
+    }
+
+    pub fn bandwidth_mhz(&self) -> Option<u16> {
+        if self.known & Self::KNOWN_BW == 0 {
+            return None;
+        }
+        match self.flags & 0x03 {
+            1 => Some(40),
+            _ => Some(20),
+        }
+    }
+
+    pub fn is_short_gi(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_GI != 0).then_some(self.flags & 0x04 != 0)
+    }
+
+    pub fn is_greenfield(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_FORMAT != 0).then_some(self.flags & 0x08 != 0)
+    }
+
+    pub fn is_ldpc(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_FEC != 0).then_some(self.flags & 0x10 != 0)
+    }
+
+    pub fn stbc_streams(&self) -> Option<u8> {
+        (self.known & Self::KNOWN_STBC != 0).then_some((self.flags >> 5) & 0x03)
+    }
+
+    pub fn rate_kbps(&self) -> Option<u32> {
+        let index = self.index()?;
+        if index > 31 {
+            return None;
+        }
+        let nsd = match self.bandwidth_mhz()? {
+            40 => 108,
+            _ => 52,
+        };
+        let gi = if self.is_short_gi().unwrap_or(false) {
+            GuardInterval::Short
+        } else {
+            GuardInterval::Long
+        };
+        ofdm_rate_kbps(nsd, index % 8, index / 8 + 1, gi.symbol_ns())
+    }
+}
+
+impl TryFrom<Field<'_>> for MCS {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::MCS(&[known, flags, index, ..]) => Ok(MCS {
+                known,
+                flags,
+                index,
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AMPDUStatus {
+    pub reference: u32,
+    flags: u16,
+    delimiter_crc: u8,
+}
+
+impl AMPDUStatus {
+    pub const LAST_KNOWN: u16 = 0x0004;
+    pub const IS_LAST: u16 = 0x0008;
+    pub const DELIM_CRC_ERR: u16 = 0x0010;
+    pub const DELIM_CRC_KNOWN: u16 = 0x0020;
+
+    pub fn is_last(&self) -> Option<bool> {
+        (self.flags & Self::LAST_KNOWN != 0).then_some(self.flags & Self::IS_LAST != 0)
+    }
+
+    pub fn is_delimiter_crc_error(&self) -> bool {
+        self.flags & Self::DELIM_CRC_ERR != 0
+    }
+
+    pub fn delimiter_crc(&self) -> Option<u8> {
+        (self.flags & Self::DELIM_CRC_KNOWN != 0).then_some(self.delimiter_crc)
+    }
+}
+
+impl TryFrom<Field<'_>> for AMPDUStatus {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::AMPDUStatus(&[r0, r1, r2, r3, f0, f1, delimiter_crc, ..]) => Ok(AMPDUStatus {
+                reference: u32::from_le_bytes([r0, r1, r2, r3]),
+                flags: u16::from_le_bytes([f0, f1]),
+                delimiter_crc,
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+/// VHT (802.11ac) rate information for up to four users.
+#[derive(Debug, Clone, Copy)]
+pub struct VHT {
+    known: u16,
+    flags: u8,
+    bandwidth: u8,
+    mcs_nss: [u8; 4],
+    coding: u8,
+    pub group_id: u8,
+    pub partial_aid: u16,
+}
+
+impl VHT {
+    pub const KNOWN_STBC: u16 = 0x0001;
+    pub const KNOWN_GI: u16 = 0x0004;
+    pub const KNOWN_BEAMFORMED: u16 = 0x0020;
+    pub const KNOWN_BANDWIDTH: u16 = 0x0040;
+
+    pub fn bandwidth_mhz(&self) -> Option<u16> {
+        if self.known & Self::KNOWN_BANDWIDTH == 0 {
+            return None;
+        }
+        match self.bandwidth {
+            0 => Some(20),
+            1..=3 => Some(40),
+            4..=10 => Some(80),
+            11..=25 => Some(160),
+            _ => None,
+        }
+    }
+
+    pub fn is_short_gi(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_GI != 0).then_some(self.flags & 0x04 != 0)
+    }
+
+    pub fn is_stbc(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_STBC != 0).then_some(self.flags & 0x01 != 0)
+    }
+
+    pub fn is_beamformed(&self) -> Option<bool> {
+        (self.known & Self::KNOWN_BEAMFORMED != 0).then_some(self.flags & 0x20 != 0)
+    }
+
+    /// MCS index and number of spatial streams of `user` (0..4), if present.
+    pub fn user(&self, user: usize) -> Option<(u8, u8)> {
+        let mcs_nss = *self.mcs_nss.get(user)?;
+        let nss = mcs_nss & 0x0f;
+        (nss != 0).then_some((mcs_nss >> 4, nss))
+    }
+
+    /// LDPC coding of `user` (0..4).
+    pub fn is_ldpc(&self, user: usize) -> bool {
+        user < 4 && self.coding & 1 << user != 0
+    }
+
+    pub fn rate_kbps(&self, user: usize) -> Option<u32> {
+        let (mcs, nss) = self.user(user)?;
+        let nsd = match self.bandwidth_mhz()? {
+            20 => 52,
+            40 => 108,
+            80 => 234,
+            _ => 468,
+        };
+        let gi = if self.is_short_gi().unwrap_or(false) {
+            GuardInterval::Short
+        } else {
+            GuardInterval::Long
+        };
+        ofdm_rate_kbps(nsd, mcs, nss, gi.symbol_ns())
+    }
+}
+
+impl TryFrom<Field<'_>> for VHT {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::VHT(
+                &[k0, k1, flags, bandwidth, m0, m1, m2, m3, coding, group_id, a0, a1, ..],
+            ) => Ok(VHT {
+                known: u16::from_le_bytes([k0, k1]),
+                flags,
+                bandwidth,
+                mcs_nss: [m0, m1, m2, m3],
+                coding,
+                group_id,
+                partial_aid: u16::from_le_bytes([a0, a1]),
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamp {
+    pub timestamp: u64,
+    pub accuracy: u16,
+    unit_position: u8,
+    pub flags: u8,
+}
+
+impl Timestamp {
+    /// Timestamp converted to nanoseconds, if the unit is known and it fits.
+    pub fn nanos(&self) -> Option<u64> {
+        match self.unit_position & 0x0f {
+            0 => self.timestamp.checked_mul(1_000_000),
+            1 => self.timestamp.checked_mul(1_000),
+            2 => Some(self.timestamp),
+            _ => None,
+        }
+    }
+
+    pub fn sampling_position(&self) -> u8 {
+        self.unit_position >> 4
+    }
+}
+
+impl TryFrom<Field<'_>> for Timestamp {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::Timestamp(
+                &[t0, t1, t2, t3, t4, t5, t6, t7, a0, a1, unit_position, flags, ..],
+            ) => Ok(Timestamp {
+                timestamp: u64::from_le_bytes([t0, t1, t2, t3, t4, t5, t6, t7]),
+                accuracy: u16::from_le_bytes([a0, a1]),
+                unit_position,
+                flags,
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HEPPDUFormat {
+    SU,
+    ExtendedSU,
+    MU,
+    TriggerBased,
+}
+
+/// HE (802.11ax) data fields.
+#[derive(Debug, Clone, Copy)]
+pub struct HE {
+    data: [u16; 6],
+}
+
+impl HE {
+    pub const DATA1_BSS_COLOR_KNOWN: u16 = 0x0004;
+    pub const DATA1_DATA_MCS_KNOWN: u16 = 0x0020;
+    pub const DATA1_DATA_DCM_KNOWN: u16 = 0x0040;
+    pub const DATA1_CODING_KNOWN: u16 = 0x0080;
+    pub const DATA1_STBC_KNOWN: u16 = 0x0200;
+    pub const DATA1_BW_RU_ALLOC_KNOWN: u16 = 0x4000;
+    pub const DATA2_GI_KNOWN: u16 = 0x0002;
+    pub const DATA2_TXBF_KNOWN: u16 = 0x0010;
+
+    /// Raw data field `idx`, numbered 1 to 6 as in the radiotap definition.
+    pub fn data(&self, idx: usize) -> Option<u16> {
+        self.data.get(idx.checked_sub(1)?).copied()
+    }
+
+    pub fn ppdu_format(&self) -> HEPPDUFormat {
+        match self.data[0] & 0x0003 {
+            0 => HEPPDUFormat::SU,
+            1 => HEPPDUFormat::ExtendedSU,
+            2 => HEPPDUFormat::MU,
+            _ => HEPPDUFormat::TriggerBased,
+        }
+    }
+
+    pub fn bss_color(&self) -> Option<u8> {
+        (self.data[0] & Self::DATA1_BSS_COLOR_KNOWN != 0).then_some((self.data[2] & 0x003f) as u8)
+    }
+
+    pub fn mcs(&self) -> Option<u8> {
+        (self.data[0] & Self::DATA1_DATA_MCS_KNOWN != 0)
+            .then_some(((self.data[2] & 0x0f00) >> 8) as u8)
+    }
+
+    pub fn is_dcm(&self) -> Option<bool> {
+        (self.data[0] & Self::DATA1_DATA_DCM_KNOWN != 0).then_some(self.data[2] & 0x1000 != 0)
+    }
+
+    pub fn is_ldpc(&self) -> Option<bool> {
+        (self.data[0] & Self::DATA1_CODING_KNOWN != 0).then_some(self.data[2] & 0x2000 != 0)
+    }
+
+    pub fn is_stbc(&self) -> Option<bool> {
+        (self.data[0] & Self::DATA1_STBC_KNOWN != 0).then_some(self.data[2] & 0x8000 != 0)
+    }
+
+    /// Raw data bandwidth / RU allocation value of data5.
+    pub fn bw_ru_allocation(&self) -> Option<u8> {
+        (self.data[0] & Self::DATA1_BW_RU_ALLOC_KNOWN != 0).then_some((self.data[4] & 0x000f) as u8)
+    }
+
+    pub fn bandwidth_mhz(&self) -> Option<u16> {
+        match self.bw_ru_allocation()? {
+            0 | 4..=7 => Some(20),
+            1 | 8 => Some(40),
+            2 | 9 => Some(80),
+            3 | 10 => Some(160),
+            _ => None,
+        }
+    }
+
+    /// Number of data subcarriers of the allocated RU.
+    fn data_subcarriers(&self) -> Option<u32> {
+        match self.bw_ru_allocation()? {
+            4 => Some(24),
+            5 => Some(48),
+            6 => Some(102),
+            0 | 7 => Some(234),
+            1 | 8 => Some(468),
+            2 | 9 => Some(980),
+            3 | 10 => Some(1960),
+            _ => None,
+        }
+    }
+
+    pub fn gi(&self) -> Option<GuardInterval> {
+        if self.data[1] & Self::DATA2_GI_KNOWN == 0 {
+            return None;
+        }
+        match (self.data[4] & 0x0030) >> 4 {
+            0 => Some(GuardInterval::HE0_8),
+            1 => Some(GuardInterval::HE1_6),
+            2 => Some(GuardInterval::HE3_2),
+            _ => None,
+        }
+    }
+
+    pub fn is_beamformed(&self) -> Option<bool> {
+        (self.data[1] & Self::DATA2_TXBF_KNOWN != 0).then_some(self.data[4] & 0x4000 != 0)
+    }
+
+    /// Number of space-time streams, 0 when unknown.
+    pub fn nsts(&self) -> u8 {
+        (self.data[5] & 0x000f) as u8
+    }
+
+    pub fn nss(&self) -> Option<u8> {
+        match (self.nsts(), self.is_stbc()) {
+            (0, _) => None,
+            (nsts, Some(true)) => Some(nsts.div_ceil(2)),
+            (nsts, _) => Some(nsts),
+        }
+    }
+
+    pub fn rate_kbps(&self) -> Option<u32> {
+        let mcs = self.mcs()?;
+        let nss = self.nss()?;
+        let nsd = self.data_subcarriers()?;
+        let rate = ofdm_rate_kbps(nsd, mcs, nss, self.gi()?.symbol_ns())?;
+        // DCM transmits every bit twice.
+        Some(if self.is_dcm() == Some(true) {
+            rate / 2
+        } else {
+            rate
+        })
+    }
+}
+
+impl TryFrom<Field<'_>> for HE {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::HE(bytes) if bytes.len() >= 12 => {
+                let mut data = [0u16; 6];
+                for (word, chunk) in data.iter_mut().zip(bytes.chunks_exact(2)) {
+                    *word = u16::from_le_bytes([chunk[0], chunk[1]]);
+                }
+                Ok(HE { data })
+            }
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HEMU {
+    pub flags1: u16,
+    pub flags2: u16,
+    pub ru_channel1: [u8; 4],
+    pub ru_channel2: [u8; 4],
+}
+
+impl HEMU {
+    pub fn sig_b_mcs(&self) -> u8 {
+        (self.flags1 & 0x000f) as u8
+    }
+
+    pub fn is_sig_b_dcm(&self) -> bool {
+        self.flags1 & 0x0020 != 0
+    }
+}
+
+impl TryFrom<Field<'_>> for HEMU {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::HEMU(&[f0, f1, f2, f3, c0, c1, c2, c3, c4, c5, c6, c7, ..]) => Ok(HEMU {
+                flags1: u16::from_le_bytes([f0, f1]),
+                flags2: u16::from_le_bytes([f2, f3]),
+                ru_channel1: [c0, c1, c2, c3],
+                ru_channel2: [c4, c5, c6, c7],
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HEMUOtherUser {
+    pub per_user1: u16,
+    pub per_user2: u16,
+    pub per_user_position: u8,
+    pub per_user_known: u8,
+}
+
+impl TryFrom<Field<'_>> for HEMUOtherUser {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::HEMUOtherUser(&[a0, a1, b0, b1, per_user_position, per_user_known, ..]) => {
+                Ok(HEMUOtherUser {
+                    per_user1: u16::from_le_bytes([a0, a1]),
+                    per_user2: u16::from_le_bytes([b0, b1]),
+                    per_user_position,
+                    per_user_known,
+                })
+            }
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+/// Legacy signal field of an OFDM PPDU.
+#[derive(Debug, Clone, Copy)]
+pub struct LSIG {
+    data1: u16,
+    data2: u16,
+}
+
+impl LSIG {
+    pub fn rate(&self) -> Option<u8> {
+        (self.data1 & 0x0001 != 0).then_some((self.data2 & 0x000f) as u8)
+    }
+
+    pub fn length(&self) -> Option<u16> {
+        (self.data1 & 0x0002 != 0).then_some(self.data2 >> 4)
+    }
+}
+
+impl TryFrom<Field<'_>> for LSIG {
+    type Error = InvalidField;
+
+    fn try_from(value: Field<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Field::LSIG(&[a0, a1, b0, b1, ..]) => Ok(LSIG {
+                data1: u16::from_le_bytes([a0, a1]),
+                data2: u16::from_le_bytes([b0, b1]),
+            }),
+            _ => Err(InvalidField {}),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardInterval {
+    Long,
+    Short,
+    HE0_8,
+    HE1_6,
+    HE3_2,
+}
+
+impl GuardInterval {
+    /// OFDM symbol duration including this guard interval.
+    pub fn symbol_ns(&self) -> u32 {
+        match self {
+            GuardInterval::Long => 4000,
+            GuardInterval::Short => 3600,
+            GuardInterval::HE0_8 => 13600,
+            GuardInterval::HE1_6 => 14400,
+            GuardInterval::HE3_2 => 16000,
+        }
+    }
+}
+
+/// Data rate of an HT/VHT/HE/EHT PPDU from its data subcarrier count, MCS
+/// index (modulation and coding rate), spatial streams and symbol duration.
+pub fn ofdm_rate_kbps(nsd: u32, mcs: u8, nss: u8, symbol_ns: u32) -> Option<u32> {
+    let (bits, num, den) = match mcs {
+        0 => (1, 1, 2),
+        1 => (2, 1, 2),
+        2 => (2, 3, 4),
+        3 => (4, 1, 2),
+        4 => (4, 3, 4),
+        5 => (6, 2, 3),
+        6 => (6, 3, 4),
+        7 => (6, 5, 6),
+        8 => (8, 3, 4),
+        9 => (8, 5, 6),
+        10 => (10, 3, 4),
+        11 => (10, 5, 6),
+        12 => (12, 3, 4),
+        13 => (12, 5, 6),
+        _ => return None,
+    };
+    let bits_per_symbol = nsd as u64 * bits * num * nss as u64;
+    Some((bits_per_symbol * 1_000_000 / (den * symbol_ns as u64)) as u32)
+}
+
+/// Radio information of a received frame collected from its radiotap header.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub tsft: Option<TSFT>,
+    pub flags: Option<Flags>,
+    pub rate: Option<Rate>,
+    pub channel: Option<Channel>,
+    pub signal: Option<AntennaSignal>,
+    pub noise: Option<AntennaNoise>,
+    pub antenna_signals: Vec<(u8, AntennaSignal)>,
+    pub mcs: Option<MCS>,
+    pub vht: Option<VHT>,
+    pub he: Option<HE>,
+}
+
+impl Summary {
+    /// PHY data rate of the frame, from the most specific rate field present.
+    pub fn phy_rate_kbps(&self) -> Option<u32> {
+        if let Some(rate) = self.he.as_ref().and_then(HE::rate_kbps) {
+            return Some(rate);
+        }
+        if let Some(rate) = self.vht.as_ref().and_then(|vht| vht.rate_kbps(0)) {
+            return Some(rate);
+        }
+        if let Some(rate) = self.mcs.as_ref().and_then(MCS::rate_kbps) {
+            return Some(rate);
+        }
+        self.rate.as_ref().map(Rate::kbps)
+    }
+
+    pub fn bandwidth_mhz(&self) -> Option<u16> {
+        self.he
+            .as_ref()
+            .and_then(HE::bandwidth_mhz)
+            .or_else(|| self.vht.as_ref().and_then(VHT::bandwidth_mhz))
+            .or_else(|| self.mcs.as_ref().and_then(MCS::bandwidth_mhz))
+    }
+}
+
+impl TryFrom<Iter<'_>> for Summary {
+    type Error = ParseError;
+
+    fn try_from(mut iter: Iter) -> Result<Self, Self::Error> {
+        let mut this = Summary::default();
+        while let Some(field) = iter.next() {
+            let field = field?;
+            if iter.namespace() != 0 {
+                if let (Some(antenna), Ok(signal)) =
+                    (iter.antenna(), AntennaSignal::try_from(field))
+                {
+                    this.antenna_signals.push((antenna, signal));
+                }
+                continue;
+            }
+            match field {
+                Field::TSFT(_) => this.tsft = TSFT::try_from(field).ok(),
+                Field::Flags(_) => this.flags = Flags::try_from(field).ok(),
+                Field::Rate(_) => this.rate = Rate::try_from(field).ok(),
+                Field::Channel(_) => this.channel = Channel::try_from(field).ok(),
+                Field::AntennaSignal(_) => this.signal = AntennaSignal::try_from(field).ok(),
+                Field::AntennaNoise(_) => this.noise = AntennaNoise::try_from(field).ok(),
+                Field::MCS(_) => this.mcs = MCS::try_from(field).ok(),
+                Field::VHT(_) => this.vht = VHT::try_from(field).ok(),
+                Field::HE(_) => this.he = HE::try_from(field).ok(),
+                _ => (),
+            }
+        }
+        Ok(this)
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        if let Some(channel) = &self.channel {
+            write!(f, "{}{} MHz", sep, channel.frequency_mhz())?;
+            sep = ", ";
+        }
+        if let Some(signal) = &self.signal {
+            write!(f, "{}{} dBm", sep, signal.dbm())?;
+            sep = ", ";
+        }
+        for (antenna, signal) in &self.antenna_signals {
+            write!(f, "{}ant{} {} dBm", sep, antenna, signal.dbm())?;
+            sep = ", ";
+        }
+        if let Some(rate) = self.phy_rate_kbps() {
+            write!(f, "{}{}.{} Mb/s", sep, rate / 1000, rate % 1000 / 100)?;
+            sep = ", ";
+        }
+        if let Some(bandwidth) = self.bandwidth_mhz() {
+            write!(f, "{}{} MHz wide", sep, bandwidth)?;
+            sep = ", ";
+        }
+        if let Some(tsft) = &self.tsft {
+            write!(f, "{}TSFT {} us", sep, tsft.micros())?;
+        }
+        Ok(())
+    }
+}
+
+/// Transmit parameters of an injected frame, serialized as its radiotap header.
+/// Fields left as `None` or zero are omitted and chosen by the driver.
+#[derive(Debug, Clone, Default)]
+pub struct TxParams {
+    /// Radiotap flags such as `Flags::SHORT_PREAMBLE`.
+    pub flags: u8,
+    /// Legacy rate in units of 500 kb/s.
+    pub rate: Option<u8>,
+    /// Transmit flags such as `TxFlags::NO_ACK` and `TxFlags::NO_SEQ`.
+    pub tx_flags: u16,
+    /// Number of retransmissions of unacknowledged frames.
+    pub data_retries: Option<u8>,
+    pub mcs: Option<TxMCS>,
+    pub vht: Option<TxVHT>,
+}
+
+/// HT rate of an injected frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TxMCS {
+    pub index: u8,
+    pub bandwidth_40: bool,
+    pub short_gi: bool,
+    pub ldpc: bool,
+    pub stbc_streams: u8,
+}
+
+/// VHT rate of an injected frame.
+#[derive(Debug, Clone, Copy)]
+pub struct TxVHT {
+    pub mcs: u8,
+    pub nss: u8,
+    /// 20, 40, 80 or 160, other widths are sent as 20 MHz.
+    pub bandwidth_mhz: u16,
+    pub short_gi: bool,
+    pub ldpc: bool,
+    pub stbc: bool,
+}
+
+impl TxParams {
+    const BIT_FLAGS: usize = 1;
+    const BIT_RATE: usize = 2;
+    const BIT_TX_FLAGS: usize = 15;
+    const BIT_DATA_RETRIES: usize = 17;
+    const BIT_MCS: usize = 19;
+    const BIT_VHT: usize = 21;
+
+    /// Serializes the parameters as a radiotap header with a single present word.
+    pub fn to_header(&self) -> Vec<u8> {
+        let mut writer = HeaderWriter::new();
+        if self.flags != 0 {
+            writer.field(Self::BIT_FLAGS, &[self.flags]);
+        }
+        if let Some(rate) = self.rate {
+            writer.field(Self::BIT_RATE, &[rate]);
+        }
+        if self.tx_flags != 0 {
+            writer.field(Self::BIT_TX_FLAGS, &self.tx_flags.to_le_bytes());
+        }
+        if let Some(retries) = self.data_retries {
+            writer.field(Self::BIT_DATA_RETRIES, &[retries]);
+        }
+        if let Some(mcs) = &self.mcs {
+            let known =
+                MCS::KNOWN_BW | MCS::KNOWN_MCS | MCS::KNOWN_GI | MCS::KNOWN_FEC | MCS::KNOWN_STBC;
+            let flags = mcs.bandwidth_40 as u8
+                | (mcs.short_gi as u8) << 2
+                | (mcs.ldpc as u8) << 4
+                | (mcs.stbc_streams & 0x03) << 5;
+            writer.field(Self::BIT_MCS, &[known, flags, mcs.index]);
+        }
+        if let Some(vht) = &self.vht {
+            let known = VHT::KNOWN_STBC | VHT::KNOWN_GI | VHT::KNOWN_BANDWIDTH;
+            let bandwidth = match vht.bandwidth_mhz {
+                40 => 1,
+                80 => 4,
+                160 => 11,
+                _ => 0,
+            };
+            let mut field = [0u8; 12];
+            field[..2].copy_from_slice(&known.to_le_bytes());
+            field[2] = vht.stbc as u8 | (vht.short_gi as u8) << 2;
+            field[3] = bandwidth;
+            field[4] = vht.mcs << 4 | vht.nss & 0x0f;
+            field[8] = vht.ldpc as u8;
+            writer.field(Self::BIT_VHT, &field);
+        }
+        writer.finish()
+    }
+}
+
+/// Appends radiotap fields in present bit order, aligned as described in [`FIELDS`].
+struct HeaderWriter {
+    buf: Vec<u8>,
+    present: u32,
+}
+
+impl HeaderWriter {
+    fn new() -> Self {
+        Self {
+            buf: vec![0; core::mem::size_of::<Header>()],
+            present: 0,
+        }
+    }
+
+    fn field(&mut self, bit: usize, data: &[u8]) {
+        let def = &FIELDS[bit];
+        debug_assert_eq!(data.len(), def.size);
+        debug_assert!(self.present >> bit == 0, "fields out of order");
+        let offset = self.buf.len().next_multiple_of(def.alignment);
+        self.buf.resize(offset, 0);
+        self.buf.extend_from_slice(data);
+        self.present |= 1 << bit;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let len = self.buf.len() as u16;
+        self.buf[2..4].copy_from_slice(&len.to_le_bytes());
+        self.buf[4..8].copy_from_slice(&self.present.to_le_bytes());
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_header() {
+        let rtap: &[u8] = &[0, 0, 8, 0, 0, 0];
+        assert_eq!(
+            RadioTap::len(rtap),
+            Err(ParseError::Truncated { need: 8, have: 6 })
+        );
+        assert!(RadioTap::iter(rtap).is_err());
+    }
+
+    #[test]
+    fn length_below_header() {
+        let rtap: &[u8] = &[0, 0, 4, 0, 0, 0, 0, 0];
+        assert_eq!(RadioTap::len(rtap), Err(ParseError::BadRadioTapLength(4)));
+        assert_eq!(
+            RadioTap::iter(rtap).err(),
+            Some(ParseError::BadRadioTapLength(4))
+        );
+    }
+
+    #[test]
+    fn length_above_buffer() {
+        let rtap: &[u8] = &[0, 0, 12, 0, 0x02, 0, 0, 0, 0x10];
+        assert_eq!(RadioTap::len(rtap), Err(ParseError::BadRadioTapLength(12)));
+        assert_eq!(
+            RadioTap::iter(rtap).err(),
+            Some(ParseError::BadRadioTapLength(12))
+        );
+    }
+
+    #[test]
+    fn unterminated_present_chain() {
+        // Every present word up to the header length sets the extension bit.
+        let rtap: &[u8] = &[0, 0, 16, 0, 0, 0, 0, 0x80, 0, 0, 0, 0x80, 0, 0, 0, 0x80];
+        assert_eq!(
+            RadioTap::iter(rtap).err(),
+            Some(ParseError::BadRadioTapLength(16))
+        );
+        // The header length cuts the second present word in half.
+        let rtap: &[u8] = &[0, 0, 10, 0, 0, 0, 0, 0x80, 0, 0];
+        assert_eq!(
+            RadioTap::iter(rtap).err(),
+            Some(ParseError::BadRadioTapLength(10))
+        );
+    }
+
+    #[test]
+    fn truncated_field() {
+        // TSFT and Flags present, but only 4 of the 8 TSFT bytes in the header.
+        let rtap: &[u8] = &[0, 0, 12, 0, 0x03, 0, 0, 0, 1, 2, 3, 4];
+        let mut iter = RadioTap::iter(rtap).unwrap();
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseError::Truncated { need: 8, have: 4 }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn timestamp_overflow() {
+        let mut field = [0xff; 12];
+        field[10] = 0; // milliseconds
+        let timestamp = Timestamp::try_from(Field::Timestamp(&field)).unwrap();
+        assert_eq!(timestamp.nanos(), None);
+        field[..8].copy_from_slice(&5u64.to_le_bytes());
+        field[10] = 1; // microseconds
+        let timestamp = Timestamp::try_from(Field::Timestamp(&field)).unwrap();
+        assert_eq!(timestamp.nanos(), Some(5_000));
+    }
+
+    #[test]
+    fn he_data_index() {
+        let field: Vec<u8> = (1..=6u16).flat_map(u16::to_le_bytes).collect();
+        let he = HE::try_from(Field::HE(&field)).unwrap();
+        assert_eq!(he.data(0), None);
+        assert_eq!(he.data(1), Some(1));
+        assert_eq!(he.data(6), Some(6));
+        assert_eq!(he.data(7), None);
+    }
+
+    #[test]
+    fn vht_ldpc_user() {
+        let mut field = [0; 12];
+        field[8] = 0xff;
+        let vht = VHT::try_from(Field::VHT(&field)).unwrap();
+        assert!(vht.is_ldpc(3));
+        assert!(!vht.is_ldpc(4));
+        assert!(!vht.is_ldpc(8));
+    }
+
+    #[test]
+    fn unterminated_present_word() {
+        // The only present word sets the extension bit, alone and with the vendor namespace bit.
+        for rtap in [[0, 0, 8, 0, 0, 0, 0, 0x80], [0, 0, 8, 0, 0, 0, 0, 0xa0]] {
+            assert_eq!(
+                RadioTap::iter(&rtap[..]).err(),
+                Some(ParseError::BadRadioTapLength(8))
+            );
+        }
+    }
+
+    #[test]
+    fn vendor_namespace() {
+        let flags: u32 = 1 << 1 | 1 << 30 | 1 << 31;
+        // Vendor namespace with one field, then back to a radiotap namespace.
+        let vendor: u32 = 1 | 1 << 29 | 1 << 31;
+        let antenna: u32 = 1 << 5 | 1 << 11;
+        let mut rtap = vec![0, 0, 28, 0];
+        for word in [flags, vendor, antenna] {
+            rtap.extend(word.to_le_bytes());
+        }
+        rtap.push(Flags::FCS);
+        // Padding, OUI, sub-namespace, skip length and the vendor data.
+        rtap.extend([0, 0x00, 0x11, 0x22, 7, 2, 0, 0xaa, 0xbb]);
+        rtap.extend([0xd8, 2]);
+        let mut iter = RadioTap::iter(rtap.as_slice()).unwrap();
+        assert!(matches!(iter.next(), Some(Ok(Field::Flags(&[Flags::FCS])))));
+        assert!(matches!(
+            iter.next(),
+            Some(Ok(Field::Vendor {
+                oui: [0x00, 0x11, 0x22],
+                sub_namespace: 7,
+                data: &[0xaa, 0xbb]
+            }))
+        ));
+        assert!(matches!(
+            iter.next(),
+            Some(Ok(Field::AntennaSignal(&[0xd8])))
+        ));
+        assert_eq!((iter.namespace(), iter.antenna()), (1, Some(2)));
+        assert!(matches!(iter.next(), Some(Ok(Field::Antenna(&[2])))));
+        assert!(iter.next().is_none());
+    }
+}
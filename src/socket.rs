@@ -1,7 +1,11 @@
 extern crate libc;
 
+use crate::radiotap::TxParams;
 use libc::{c_int, c_ushort, c_void};
-use std::{io, mem, ptr};
+use std::ops::AddAssign;
+use std::sync::atomic::{fence, Ordering};
+use std::time::Duration;
+use std::{io, mem, ptr, slice};
 
 pub struct Socket {
     fd: c_int,
@@ -27,6 +31,51 @@ impl Socket {
         let res = v2r(unsafe { libc::send(self.fd, buf.as_ptr() as *mut c_void, buf.len(), 0) })?;
         Ok(res as usize)
     }
+
+    pub fn set_option<T>(&self, level: c_int, name: c_int, value: &T) -> io::Result<()> {
+        v2r(unsafe {
+            libc::setsockopt(
+                self.fd,
+                level,
+                name,
+                (value as *const T).cast(),
+                mem::size_of::<T>() as libc::socklen_t,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Reads an option into `value`, returning the length filled in by the kernel.
+    pub fn get_option<T>(&self, level: c_int, name: c_int, value: &mut T) -> io::Result<usize> {
+        let mut len = mem::size_of::<T>() as libc::socklen_t;
+        v2r(unsafe { libc::getsockopt(self.fd, level, name, (value as *mut T).cast(), &mut len) })?;
+        Ok(len as usize)
+    }
+
+    /// Makes `recv` fail with `WouldBlock` after `timeout`, or block forever with `None`.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        let timeout = timeout.unwrap_or_default();
+        let tv = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        self.set_option(libc::SOL_SOCKET, libc::SO_RCVTIMEO, &tv)
+    }
+
+    /// Waits until the socket is readable, returning false if `timeout`
+    /// elapsed or a signal arrived first.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let mut pfd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN | libc::POLLERR,
+            revents: 0,
+        };
+        let timeout = timeout.map_or(-1, |timeout| timeout.as_millis() as c_int);
+        match v2r(unsafe { libc::poll(&mut pfd, 1, timeout) }) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => Ok(false),
+            res => res.map(|n| n > 0),
+        }
+    }
 }
 
 impl Drop for Socket {
@@ -66,6 +115,402 @@ impl PacketSocket {
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.recv(buf)
     }
+
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(timeout)
+    }
+
+    /// Transmits an 802.11 frame, without FCS, preceded by a radiotap header
+    /// built from `params`. The socket must be bound to a monitor interface.
+    pub fn inject(&self, frame: &[u8], params: &TxParams) -> io::Result<()> {
+        let mut packet = params.to_header();
+        packet.extend_from_slice(frame);
+        let sent = self.0.send(&packet)?;
+        if sent != packet.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                format!("sent {} of {} bytes", sent, packet.len()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Attaches a classic BPF program, frames it rejects are dropped before
+    /// being queued to the socket.
+    pub fn attach_filter(&self, program: &[libc::sock_filter]) -> io::Result<()> {
+        let fprog = libc::sock_fprog {
+            len: program.len() as c_ushort,
+            filter: program.as_ptr() as *mut libc::sock_filter,
+        };
+        self.0
+            .set_option(libc::SOL_SOCKET, libc::SO_ATTACH_FILTER, &fprog)
+    }
+
+    pub fn detach_filter(&self) -> io::Result<()> {
+        self.0
+            .set_option(libc::SOL_SOCKET, libc::SO_DETACH_FILTER, &0 as &c_int)
+    }
+
+    /// Kernel counters since the previous call, the kernel resets them on every read.
+    pub fn statistics(&self) -> io::Result<PacketStatistics> {
+        let mut stats = TPacketStatsV3 {
+            tp_packets: 0,
+            tp_drops: 0,
+            tp_freeze_q_cnt: 0,
+        };
+        // Without TPACKET_V3 the kernel only fills in the first two counters.
+        self.0
+            .get_option(libc::SOL_PACKET, PACKET_STATISTICS, &mut stats)?;
+        Ok(PacketStatistics {
+            packets: stats.tp_packets as u64,
+            drops: stats.tp_drops as u64,
+            freeze_queue_count: stats.tp_freeze_q_cnt as u64,
+        })
+    }
+
+    /// Enables receive timestamps for `recv_timestamped` and the receive ring.
+    /// With `hardware`, timestamps taken by the NIC are preferred when the
+    /// driver provides them.
+    pub fn enable_timestamps(&self, hardware: bool) -> io::Result<()> {
+        self.0
+            .set_option(libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, &1 as &c_int)?;
+        if hardware {
+            let flags = libc::SOF_TIMESTAMPING_RX_HARDWARE
+                | libc::SOF_TIMESTAMPING_RAW_HARDWARE
+                | libc::SOF_TIMESTAMPING_RX_SOFTWARE
+                | libc::SOF_TIMESTAMPING_SOFTWARE;
+            self.0
+                .set_option(libc::SOL_SOCKET, libc::SO_TIMESTAMPING, &flags)?;
+            let ring_flags = libc::SOF_TIMESTAMPING_RAW_HARDWARE;
+            self.0
+                .set_option(libc::SOL_PACKET, PACKET_TIMESTAMP, &ring_flags)?;
+        }
+        Ok(())
+    }
+
+    /// Receives a frame along with its receive timestamp, if timestamps are enabled.
+    pub fn recv_timestamped(&self, buf: &mut [u8]) -> io::Result<(usize, Option<RxTimestamp>)> {
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr().cast(),
+            iov_len: buf.len(),
+        };
+        // Room for both SCM_TIMESTAMPNS and SCM_TIMESTAMPING, aligned for cmsghdr.
+        let mut control = [0u64; 16];
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = mem::size_of_val(&control) as _;
+        let len = v2r(unsafe { libc::recvmsg(self.0.fd(), &mut msg, 0) })? as usize;
+
+        let mut software = None;
+        let mut hardware = None;
+        let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+        while !cmsg.is_null() {
+            let (level, ty) = unsafe { ((*cmsg).cmsg_level, (*cmsg).cmsg_type) };
+            let data = unsafe { libc::CMSG_DATA(cmsg) }.cast::<libc::timespec>();
+            if level == libc::SOL_SOCKET && ty == libc::SCM_TIMESTAMPNS {
+                software = timespec_duration(unsafe { ptr::read_unaligned(data) });
+            } else if level == libc::SOL_SOCKET && ty == libc::SCM_TIMESTAMPING {
+                // Software, legacy and raw hardware timestamps, zero when missing.
+                let ts = unsafe { ptr::read_unaligned(data.cast::<[libc::timespec; 3]>()) };
+                software = software.or(timespec_duration(ts[0]));
+                hardware = timespec_duration(ts[2]);
+            }
+            cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
+        }
+        let timestamp = match (hardware, software) {
+            (Some(time), _) => Some(RxTimestamp {
+                time,
+                hardware: true,
+            }),
+            (None, Some(time)) => Some(RxTimestamp {
+                time,
+                hardware: false,
+            }),
+            (None, None) => None,
+        };
+        Ok((len, timestamp))
+    }
+}
+
+/// Receive time of a frame since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RxTimestamp {
+    pub time: Duration,
+    /// Taken by the NIC rather than by the kernel.
+    pub hardware: bool,
+}
+
+fn timespec_duration(ts: libc::timespec) -> Option<Duration> {
+    if ts.tv_sec == 0 && ts.tv_nsec == 0 {
+        None
+    } else {
+        Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+    }
+}
+
+const PACKET_STATISTICS: c_int = 6;
+const PACKET_TIMESTAMP: c_int = 17;
+
+/// Packet socket counters from `PACKET_STATISTICS`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PacketStatistics {
+    /// Frames that reached the socket, including the dropped ones.
+    pub packets: u64,
+    /// Frames dropped because the receive buffer or ring was full.
+    pub drops: u64,
+    /// Times the TPACKET_V3 ring was full and the kernel stopped filling it.
+    pub freeze_queue_count: u64,
+}
+
+impl AddAssign for PacketStatistics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.packets += rhs.packets;
+        self.drops += rhs.drops;
+        self.freeze_queue_count += rhs.freeze_queue_count;
+    }
+}
+
+#[repr(C)]
+struct TPacketStatsV3 {
+    tp_packets: u32,
+    tp_drops: u32,
+    tp_freeze_q_cnt: u32,
+}
+
+impl PacketSocket {
+    /// Switches the socket to a memory-mapped TPACKET_V3 receive ring.
+    /// Must be called before `bind` so no frame is queued outside the ring.
+    pub fn rx_ring(&self, config: &RingConfig) -> io::Result<RxRing<'_>> {
+        self.0
+            .set_option(libc::SOL_PACKET, PACKET_VERSION, &TPACKET_V3)?;
+        let req = TPacketReq3 {
+            tp_block_size: config.block_size,
+            tp_block_nr: config.block_count,
+            tp_frame_size: RingConfig::FRAME_SIZE,
+            tp_frame_nr: config.block_size / RingConfig::FRAME_SIZE * config.block_count,
+            tp_retire_blk_tov: config.retire_timeout_ms,
+            tp_sizeof_priv: 0,
+            tp_feature_req_word: 0,
+        };
+        self.0.set_option(libc::SOL_PACKET, PACKET_RX_RING, &req)?;
+        let map_len = config.block_size as usize * config.block_count as usize;
+        let map = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                self.0.fd(),
+                0,
+            )
+        };
+        if map == libc::MAP_FAILED {
+            let err = io::Error::last_os_error();
+            // Tear the ring down again, otherwise the kernel keeps queueing frames into it
+            // and a caller falling back to recv would never see them.
+            let _ = self
+                .0
+                .set_option(libc::SOL_PACKET, PACKET_RX_RING, &TPacketReq3::default());
+            return Err(err);
+        }
+        Ok(RxRing {
+            socket: self,
+            map: map.cast(),
+            map_len,
+            block_size: config.block_size as usize,
+            block_count: config.block_count as usize,
+            block: 0,
+            in_block: false,
+            pkt_offset: 0,
+            pkts_left: 0,
+        })
+    }
+}
+
+/// Geometry of a TPACKET_V3 receive ring.
+#[derive(Debug, Clone)]
+pub struct RingConfig {
+    /// Size of a block, a multiple of the page size.
+    pub block_size: u32,
+    pub block_count: u32,
+    /// Time after which the kernel hands over a block that is not full yet.
+    pub retire_timeout_ms: u32,
+}
+
+impl RingConfig {
+    /// Frames are variable-sized in TPACKET_V3, but the kernel still validates a frame size.
+    const FRAME_SIZE: u32 = 2048;
+}
+
+impl Default for RingConfig {
+    fn default() -> Self {
+        Self {
+            block_size: 64 * 1024,
+            block_count: 16,
+            retire_timeout_ms: 100,
+        }
+    }
+}
+
+/// A frame in the receive ring, valid until the next call to [`RxRing::next`].
+#[derive(Debug)]
+pub struct RingFrame<'a> {
+    pub data: &'a [u8],
+    /// Length of the frame on the wire, `data` may be shorter.
+    pub len: usize,
+    pub timestamp: RxTimestamp,
+    pub status: u32,
+}
+
+impl RingFrame<'_> {
+    /// Set when the kernel dropped frames because the ring was full.
+    pub fn is_losing(&self) -> bool {
+        self.status & TP_STATUS_LOSING != 0
+    }
+}
+
+/// Memory-mapped TPACKET_V3 receive ring. Blocks are handed back to the
+/// kernel once all their frames have been returned.
+pub struct RxRing<'a> {
+    socket: &'a PacketSocket,
+    map: *mut u8,
+    map_len: usize,
+    block_size: usize,
+    block_count: usize,
+    block: usize,
+    in_block: bool,
+    pkt_offset: usize,
+    pkts_left: u32,
+}
+
+impl RxRing<'_> {
+    /// Returns the next frame, waiting for the kernel to fill a block if needed.
+    /// Returns `None` if `timeout` elapsed or a signal arrived while waiting.
+    pub fn next(&mut self, timeout: Option<Duration>) -> io::Result<Option<RingFrame<'_>>> {
+        loop {
+            if self.pkts_left > 0 {
+                return self.next_frame().map(Some);
+            }
+            if self.in_block {
+                // Hand the consumed block back to the kernel.
+                fence(Ordering::Release);
+                unsafe { ptr::write_volatile(self.block_status(), TP_STATUS_KERNEL) };
+                self.block = (self.block + 1) % self.block_count;
+                self.in_block = false;
+            }
+            let status = unsafe { ptr::read_volatile(self.block_status()) };
+            if status & TP_STATUS_USER == 0 {
+                if !self.socket.0.wait_readable(timeout)? {
+                    return Ok(None);
+                }
+                continue;
+            }
+            fence(Ordering::Acquire);
+            let desc = self.block_bytes();
+            let (num_pkts, first_pkt) = (
+                read_u32(desc, BLOCK_NUM_PKTS),
+                read_u32(desc, BLOCK_OFFSET_TO_FIRST_PKT) as usize,
+            );
+            self.pkts_left = num_pkts;
+            self.pkt_offset = first_pkt;
+            self.in_block = true;
+        }
+    }
+
+    fn next_frame(&mut self) -> io::Result<RingFrame<'_>> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "corrupted ring block");
+        let offset = self.pkt_offset;
+        let hdr = self
+            .block_bytes()
+            .get(offset..offset + TPACKET3_HDR_LEN)
+            .ok_or_else(invalid)?;
+        let next_offset = read_u32(hdr, 0) as usize;
+        let sec = read_u32(hdr, 4);
+        let nsec = read_u32(hdr, 8);
+        let snap_len = read_u32(hdr, 12) as usize;
+        let len = read_u32(hdr, 16) as usize;
+        let status = read_u32(hdr, 20);
+        let data_offset = offset + u16::from_ne_bytes([hdr[24], hdr[25]]) as usize;
+
+        self.pkts_left -= 1;
+        self.pkt_offset += next_offset;
+        if next_offset == 0 {
+            self.pkts_left = 0;
+        }
+        let data = self
+            .block_bytes()
+            .get(data_offset..data_offset + snap_len)
+            .ok_or_else(invalid)?;
+        Ok(RingFrame {
+            data,
+            len,
+            timestamp: RxTimestamp {
+                time: Duration::new(sec as u64, nsec),
+                hardware: status & TP_STATUS_TS_RAW_HARDWARE != 0,
+            },
+            status,
+        })
+    }
+
+    fn block_bytes(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(self.map.add(self.block * self.block_size), self.block_size)
+        }
+    }
+
+    fn block_status(&self) -> *mut u32 {
+        unsafe {
+            self.map
+                .add(self.block * self.block_size + BLOCK_STATUS)
+                .cast()
+        }
+    }
+}
+
+impl Drop for RxRing<'_> {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.map.cast(), self.map_len) };
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+const PACKET_RX_RING: c_int = 5;
+const PACKET_VERSION: c_int = 10;
+const TPACKET_V3: c_int = 2;
+
+const TP_STATUS_KERNEL: u32 = 0;
+const TP_STATUS_USER: u32 = 1 << 0;
+const TP_STATUS_LOSING: u32 = 1 << 2;
+const TP_STATUS_TS_RAW_HARDWARE: u32 = 1 << 31;
+
+/// Offsets in `struct tpacket_block_desc` with a `tpacket_hdr_v1` header.
+const BLOCK_STATUS: usize = 8;
+const BLOCK_NUM_PKTS: usize = 12;
+const BLOCK_OFFSET_TO_FIRST_PKT: usize = 16;
+
+/// Size of `struct tpacket3_hdr` up to and including `tp_mac`.
+const TPACKET3_HDR_LEN: usize = 26;
+
+#[repr(C)]
+#[derive(Default)]
+struct TPacketReq3 {
+    tp_block_size: u32,
+    tp_block_nr: u32,
+    tp_frame_size: u32,
+    tp_frame_nr: u32,
+    tp_retire_blk_tov: u32,
+    tp_sizeof_priv: u32,
+    tp_feature_req_word: u32,
 }
 
 fn v2r<T: LibcErrorIndicator>(v: T) -> io::Result<T> {